@@ -156,6 +156,7 @@ impl BPTimerClient {
 
     /// Report HP to BPTimer API
     /// Handles validation, caching, and HTTP request internally
+    #[allow(clippy::too_many_arguments)]
     pub fn report_hp(
         self: Arc<Self>,
         monster_id: u32,
@@ -168,6 +169,7 @@ impl BPTimerClient {
         uid: Option<i64>,
         player_name: Option<String>,
         scene_ip: Option<String>,
+        server_time_ms: Option<i64>,
     ) {
         // Check API credentials
         if self.api_key.is_empty() || self.api_url.is_empty() {
@@ -253,6 +255,7 @@ impl BPTimerClient {
             "uid": uid,
             "player_name": player_name,
             "scene_ip": scene_ip,
+            "server_time_ms": server_time_ms,
         });
 
         let task = HpReportTask {
@@ -352,20 +352,18 @@ impl PocketBaseClient {
             for (idx, item) in items.iter().enumerate() {
                 match serde_json::from_value::<Mob>(item.clone()) {
                     Ok(mut mob) => {
-                        if let Some(expand) = item.get("expand") {
-                            if let Some(map) = expand.get("map") {
-                                // Read from region_data based on selected region
-                                if let Some(region_data) = map.get("region_data") {
-                                    if let Some(region_data_obj) = region_data.as_object() {
-                                        let region_str = get_region_string(&self.region);
-                                        if let Some(region_channels) =
-                                            region_data_obj.get(region_str)
-                                        {
-                                            if let Some(total_channels) = region_channels.as_i64() {
-                                                mob.total_channels = total_channels as i32;
-                                            }
-                                        }
-                                    }
+                        if let Some(expand) = item.get("expand")
+                            && let Some(map) = expand.get("map")
+                        {
+                            // Read from region_data based on selected region
+                            if let Some(region_data) = map.get("region_data")
+                                && let Some(region_data_obj) = region_data.as_object()
+                            {
+                                let region_str = get_region_string(&self.region);
+                                if let Some(region_channels) = region_data_obj.get(region_str)
+                                    && let Some(total_channels) = region_channels.as_i64()
+                                {
+                                    mob.total_channels = total_channels as i32;
                                 }
                             }
                         }
@@ -527,13 +525,12 @@ impl PocketBaseClient {
         let mut changed = false;
 
         for id in ids {
-            if let Some(mob_id) = id.as_str() {
-                if let Some(mob) = state.iter_mut().find(|m| m.id == mob_id) {
-                    if reset_mob_channels(mob) {
-                        sanitize_channels(mob);
-                        changed = true;
-                    }
-                }
+            if let Some(mob_id) = id.as_str()
+                && let Some(mob) = state.iter_mut().find(|m| m.id == mob_id)
+                && reset_mob_channels(mob)
+            {
+                sanitize_channels(mob);
+                changed = true;
             }
         }
 
@@ -595,7 +592,7 @@ fn update_channel_entry(
 }
 
 fn reset_mob_channels(mob: &mut Mob) -> bool {
-    if mob.latest_channels.as_ref().map_or(true, Vec::is_empty) {
+    if mob.latest_channels.as_ref().is_none_or(Vec::is_empty) {
         return false;
     }
     mob.latest_channels = Some(Vec::new());
@@ -644,14 +641,14 @@ fn sanitize_channels_list(channels: &mut Vec<MobChannel>) {
 }
 
 fn is_channel_stale(channel: &MobChannel, now: &DateTime<Utc>) -> bool {
-    if let Some(ts) = channel.last_updated.as_deref() {
-        if let Ok(parsed) = DateTime::parse_from_rfc3339(ts) {
-            let age = (now.timestamp() - parsed.with_timezone(&Utc).timestamp()).abs();
-            if channel.hp_percentage <= 0.0 {
-                return age > DEAD_STALE_SECS;
-            }
-            return age > ALIVE_STALE_SECS;
+    if let Some(ts) = channel.last_updated.as_deref()
+        && let Ok(parsed) = DateTime::parse_from_rfc3339(ts)
+    {
+        let age = (now.timestamp() - parsed.with_timezone(&Utc).timestamp()).abs();
+        if channel.hp_percentage <= 0.0 {
+            return age > DEAD_STALE_SECS;
         }
+        return age > ALIVE_STALE_SECS;
     }
     false
 }
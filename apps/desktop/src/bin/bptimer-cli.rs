@@ -0,0 +1,102 @@
+//! Headless meter: runs packet capture (or a replay) without the overlay and
+//! prints every `CombatEvent` to stdout as one JSON object per line.
+//!
+//! Usage:
+//!   bptimer-cli [--device <index>] [--list-devices]
+//!   bptimer-cli --replay <file> [--fast]
+//!
+//! Logs go to stderr so stdout can be piped straight into other tools.
+
+use bptimer_desktop::capture::packet::{self, PacketCapture, ReplayOptions};
+use log::error;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+fn print_usage() {
+    eprintln!("Usage: bptimer-cli [--device <index>] [--list-devices]");
+    eprintln!("       bptimer-cli --replay <file.pcap|file.pcapng|file.bpsess> [--fast]");
+}
+
+fn list_devices() -> ExitCode {
+    match pcap::Device::list() {
+        Ok(devices) => {
+            let best = packet::select_best_device(&devices);
+            for (i, dev) in devices.iter().enumerate() {
+                let marker = if Some(i) == best { " (default)" } else { "" };
+                println!("[{}] {}{}", i, packet::clean_device_name(dev), marker);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            error!("Failed to list network devices: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    let mut device_index = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--device" => match args.next().and_then(|v| v.parse::<usize>().ok()) {
+                Some(idx) => device_index = Some(idx),
+                None => {
+                    print_usage();
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--list-devices" => return list_devices(),
+            "--help" | "-h" => {
+                print_usage();
+                return ExitCode::SUCCESS;
+            }
+            // Parsed by ReplayOptions
+            "--replay" => {
+                args.next();
+            }
+            "--fast" => {}
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                print_usage();
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let capture = match ReplayOptions::from_args(std::env::args()) {
+        Ok(Some(options)) => PacketCapture::start_replay(&options),
+        Ok(None) => PacketCapture::start(device_index),
+        Err(e) => {
+            eprintln!("{}", e);
+            print_usage();
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(capture) = capture else {
+        error!("Failed to start packet capture");
+        return ExitCode::FAILURE;
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for event in capture.events() {
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Failed to serialize event: {}", e);
+                continue;
+            }
+        };
+        // Stop quietly when the consumer closes the pipe
+        if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+            break;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
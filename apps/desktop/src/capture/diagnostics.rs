@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Capture pipeline health counters, shared between the capture thread and the UI
+#[derive(Debug, Default)]
+pub struct CaptureDiagnostics {
+    /// Packets handed to the TCP processor by the packet source
+    pub packets_processed: AtomicU64,
+    // Counters reported by pcap (live capture only)
+    pub pcap_received: AtomicU64,
+    pub pcap_dropped: AtomicU64,
+    pub pcap_if_dropped: AtomicU64,
+    /// Payload bytes appended to reassembled TCP streams
+    pub bytes_reassembled: AtomicU64,
+    /// Complete game frames extracted from TCP streams
+    pub frames_extracted: AtomicU64,
+    /// Times a stream skipped a sequence gap that never filled
+    pub gap_resyncs: AtomicU64,
+    /// Stream bytes discarded while looking for a valid frame header
+    pub bytes_skipped: AtomicU64,
+    pub zstd_failures: AtomicU64,
+    /// Dirty-data objects cut short by an unknown field that could not be skipped
+    pub dirty_truncated_objects: AtomicU64,
+    /// Protobuf decode failures keyed by message name
+    decode_failures: Mutex<HashMap<String, u64>>,
+    /// Notify method IDs on the game service that we do not handle
+    unknown_methods: Mutex<HashMap<u32, u64>>,
+}
+
+/// Point-in-time copy of `CaptureDiagnostics` for display
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsSnapshot {
+    pub packets_processed: u64,
+    pub pcap_received: u64,
+    pub pcap_dropped: u64,
+    pub pcap_if_dropped: u64,
+    pub bytes_reassembled: u64,
+    pub frames_extracted: u64,
+    pub gap_resyncs: u64,
+    pub bytes_skipped: u64,
+    pub zstd_failures: u64,
+    pub dirty_truncated_objects: u64,
+    /// Sorted by count, highest first
+    pub decode_failures: Vec<(String, u64)>,
+    /// Sorted by count, highest first
+    pub unknown_methods: Vec<(u32, u64)>,
+}
+
+impl CaptureDiagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_pcap_stats(&self, received: u32, dropped: u32, if_dropped: u32) {
+        self.pcap_received.store(received as u64, Ordering::Relaxed);
+        self.pcap_dropped.store(dropped as u64, Ordering::Relaxed);
+        self.pcap_if_dropped
+            .store(if_dropped as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_decode_failure(&self, message: &str) {
+        if let Ok(mut failures) = self.decode_failures.lock() {
+            *failures.entry(message.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn record_unknown_method(&self, method_id: u32) {
+        if let Ok(mut methods) = self.unknown_methods.lock() {
+            *methods.entry(method_id).or_insert(0) += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> DiagnosticsSnapshot {
+        let mut decode_failures: Vec<(String, u64)> = self
+            .decode_failures
+            .lock()
+            .map(|f| f.iter().map(|(k, v)| (k.clone(), *v)).collect())
+            .unwrap_or_default();
+        decode_failures.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+        let mut unknown_methods: Vec<(u32, u64)> = self
+            .unknown_methods
+            .lock()
+            .map(|m| m.iter().map(|(k, v)| (*k, *v)).collect())
+            .unwrap_or_default();
+        unknown_methods.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+        DiagnosticsSnapshot {
+            packets_processed: self.packets_processed.load(Ordering::Relaxed),
+            pcap_received: self.pcap_received.load(Ordering::Relaxed),
+            pcap_dropped: self.pcap_dropped.load(Ordering::Relaxed),
+            pcap_if_dropped: self.pcap_if_dropped.load(Ordering::Relaxed),
+            bytes_reassembled: self.bytes_reassembled.load(Ordering::Relaxed),
+            frames_extracted: self.frames_extracted.load(Ordering::Relaxed),
+            gap_resyncs: self.gap_resyncs.load(Ordering::Relaxed),
+            bytes_skipped: self.bytes_skipped.load(Ordering::Relaxed),
+            zstd_failures: self.zstd_failures.load(Ordering::Relaxed),
+            dirty_truncated_objects: self.dirty_truncated_objects.load(Ordering::Relaxed),
+            decode_failures,
+            unknown_methods,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use etherparse::SlicedPacket;
+
+// pcap DLT_* values
+const DLT_NULL: i32 = 0;
+const DLT_EN10MB: i32 = 1;
+const DLT_RAW: i32 = 12;
+const DLT_RAW_BSD: i32 = 14;
+const LINKTYPE_RAW: i32 = 101;
+const DLT_LOOP: i32 = 108;
+const DLT_LINUX_SLL: i32 = 113;
+const DLT_IPV4: i32 = 228;
+const DLT_IPV6: i32 = 229;
+const DLT_LINUX_SLL2: i32 = 276;
+
+const NULL_HEADER_LEN: usize = 4;
+const SLL_HEADER_LEN: usize = 16;
+const SLL_PROTOCOL_OFFSET: usize = 14;
+const SLL2_HEADER_LEN: usize = 20;
+const SLL2_PROTOCOL_OFFSET: usize = 0;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+
+/// Link-layer framing of captured packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// Ethernet II (most physical and Wi-Fi adapters)
+    Ethernet,
+    /// Linux cooked capture v1 (the `any` device)
+    LinuxSll,
+    /// Linux cooked capture v2
+    LinuxSll2,
+    /// Bare IP packets (TUN adapters such as WireGuard/OpenVPN)
+    RawIp,
+    /// BSD/Windows loopback, 4-byte address family header
+    Null,
+}
+
+impl LinkType {
+    /// Map a pcap datalink value; None if unsupported
+    pub fn from_pcap(linktype: pcap::Linktype) -> Option<Self> {
+        match linktype.0 {
+            DLT_EN10MB => Some(LinkType::Ethernet),
+            DLT_LINUX_SLL => Some(LinkType::LinuxSll),
+            DLT_LINUX_SLL2 => Some(LinkType::LinuxSll2),
+            DLT_RAW | DLT_RAW_BSD | LINKTYPE_RAW | DLT_IPV4 | DLT_IPV6 => Some(LinkType::RawIp),
+            DLT_NULL | DLT_LOOP => Some(LinkType::Null),
+            _ => None,
+        }
+    }
+
+    /// Slice a captured frame down to its IP/TCP layers
+    pub fn slice(self, data: &[u8]) -> Option<SlicedPacket<'_>> {
+        match self {
+            LinkType::Ethernet => SlicedPacket::from_ethernet(data).ok(),
+            LinkType::LinuxSll => {
+                Self::cooked_ip_payload(data, SLL_HEADER_LEN, SLL_PROTOCOL_OFFSET)
+                    .and_then(|ip| SlicedPacket::from_ip(ip).ok())
+            }
+            LinkType::LinuxSll2 => {
+                Self::cooked_ip_payload(data, SLL2_HEADER_LEN, SLL2_PROTOCOL_OFFSET)
+                    .and_then(|ip| SlicedPacket::from_ip(ip).ok())
+            }
+            LinkType::RawIp => SlicedPacket::from_ip(data).ok(),
+            // The address family is host byte order for NULL and network byte
+            // order for LOOP; the IP version nibble is enough to tell v4 from v6
+            LinkType::Null => data
+                .get(NULL_HEADER_LEN..)
+                .and_then(|ip| SlicedPacket::from_ip(ip).ok()),
+        }
+    }
+
+    /// IP payload of a Linux cooked capture frame, None for non-IP protocols
+    fn cooked_ip_payload(data: &[u8], header_len: usize, protocol_offset: usize) -> Option<&[u8]> {
+        if data.len() < header_len {
+            return None;
+        }
+        let protocol = u16::from_be_bytes([data[protocol_offset], data[protocol_offset + 1]]);
+        match protocol {
+            ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => Some(&data[header_len..]),
+            _ => None,
+        }
+    }
+}
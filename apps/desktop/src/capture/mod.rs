@@ -1,3 +1,7 @@
+pub mod diagnostics;
+pub mod link;
 pub mod packet;
 pub mod parser;
+pub mod session;
+pub mod source;
 pub mod tcp;
@@ -1,20 +1,58 @@
-use log::error;
+use log::{error, info};
 use netdev::interface::get_interfaces;
 use netdev::interface::types::InterfaceType;
-use pcap::{Capture, Device};
-use std::sync::mpsc;
+use pcap::Device;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, mpsc};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::capture::diagnostics::{CaptureDiagnostics, DiagnosticsSnapshot};
+use crate::capture::session::{self, SessionRecord};
+use crate::capture::source::{
+    LivePcapSource, PacketSource, PcapFileSource, SessionSource, SourceEvent,
+};
 use crate::capture::tcp::TcpStreamProcessor;
 use crate::models::events::CombatEvent;
 
 #[derive(Debug)]
 enum Control {
     Switch(usize),
+    Record(bool),
 }
 
-const PCAP_SNAPLEN: i32 = 65535;
-const PCAP_TIMEOUT_MS: i32 = 10;
+/// How often pcap drop counters are refreshed
+const PCAP_STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Offline replay of a saved .pcap/.pcapng file or a recorded .bpsess session
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    pub path: PathBuf,
+    /// Sleep between packets to reproduce the original capture pace. Stats
+    /// are timed on arrival, so fast replays get DPS and durations wrong.
+    pub realtime: bool,
+}
+
+impl ReplayOptions {
+    /// Parse `--replay <file>` and `--fast` from command line arguments
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut path = None;
+        let mut realtime = true;
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replay" => match args.next() {
+                    Some(file) if !file.starts_with("--") => path = Some(PathBuf::from(file)),
+                    _ => return Err("--replay needs a capture file path".to_string()),
+                },
+                "--fast" => realtime = false,
+                _ => {}
+            }
+        }
+        Ok(path.map(|path| Self { path, realtime }))
+    }
+}
 
 pub fn clean_device_name(device: &Device) -> String {
     let device_name = device
@@ -45,27 +83,31 @@ fn get_netdev_interface<'a>(
         .find(|iface| device.name.contains(&iface.name))
 }
 
-// Check if a netdev interface has valid IPv4 addresses
-fn has_valid_ipv4_netdev(iface: &netdev::Interface) -> bool {
-    iface.ipv4.iter().any(|ipnet| {
+// Check if a netdev interface has valid IPv4 or IPv6 addresses
+fn has_valid_ip_netdev(iface: &netdev::Interface) -> bool {
+    let has_ipv4 = iface.ipv4.iter().any(|ipnet| {
         let ipv4 = ipnet.addr();
         !ipv4.is_unspecified() && !ipv4.is_loopback() && !ipv4.is_link_local()
-    })
+    });
+    let has_ipv6 = iface.ipv6.iter().any(|ipnet| {
+        let ipv6 = ipnet.addr();
+        !ipv6.is_unspecified() && !ipv6.is_loopback() && !ipv6.is_unicast_link_local()
+    });
+    has_ipv4 || has_ipv6
 }
 
 pub struct PacketCapture {
     receiver: mpsc::Receiver<CombatEvent>,
     control_tx: mpsc::Sender<Control>,
+    replay_path: Option<PathBuf>,
+    diagnostics: Arc<CaptureDiagnostics>,
 }
 
 impl PacketCapture {
     pub fn start(device_index: Option<usize>) -> Option<Self> {
         let devices = Device::list().ok()?;
-        let (tx, rx) = mpsc::channel::<CombatEvent>();
-        let (control_tx, control_rx) = mpsc::channel::<Control>();
 
         // Log available devices
-        use log::info;
         info!("Available network devices:");
         for (i, dev) in devices.iter().enumerate() {
             let clean_name = clean_device_name(dev);
@@ -82,25 +124,72 @@ impl PacketCapture {
             info!("Selected device: [{}]", initial_idx);
         }
 
+        let source = match LivePcapSource::open(devices, initial_idx) {
+            Ok(source) => source,
+            Err(e) => {
+                error!("Packet capture error: {}", e);
+                return None;
+            }
+        };
+
+        Some(Self::from_source(source, false, None))
+    }
+
+    /// Replay a saved .pcap/.pcapng file through the same TCP/parser pipeline.
+    /// Recorded sessions skip TCP reassembly and feed frames straight to the parser.
+    pub fn start_replay(options: &ReplayOptions) -> Option<Self> {
+        let source: Result<Box<dyn PacketSource>, _> = if session::is_session_file(&options.path) {
+            SessionSource::open(&options.path).map(|s| Box::new(s) as Box<dyn PacketSource>)
+        } else {
+            PcapFileSource::open(&options.path).map(|s| Box::new(s) as Box<dyn PacketSource>)
+        };
+        let source = match source {
+            Ok(source) => source,
+            Err(e) => {
+                error!("Failed to open {}: {}", options.path.display(), e);
+                return None;
+            }
+        };
+
+        info!(
+            "Replaying {} (realtime: {})",
+            options.path.display(),
+            options.realtime
+        );
+
+        Some(Self::from_source(
+            source,
+            options.realtime,
+            Some(options.path.clone()),
+        ))
+    }
+
+    /// Run any packet source on a background thread. `realtime` paces
+    /// timestamped input to its original speed; `replay_path` marks the
+    /// capture as an offline replay.
+    pub fn from_source(
+        source: impl PacketSource + 'static,
+        realtime: bool,
+        replay_path: Option<PathBuf>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel::<CombatEvent>();
+        let (control_tx, control_rx) = mpsc::channel::<Control>();
+        let diagnostics = Arc::new(CaptureDiagnostics::new());
+
+        let thread_diagnostics = diagnostics.clone();
         thread::spawn(move || {
-            let mut current_idx = initial_idx;
-
-            loop {
-                match capture_loop(tx.clone(), &devices, current_idx, &control_rx) {
-                    Ok(Some(new_idx)) => current_idx = new_idx,
-                    Ok(None) => break, // Shutdown
-                    Err(e) => {
-                        error!("Packet capture error: {}", e);
-                        break;
-                    }
-                }
+            let mut source = source;
+            if let Err(e) = run_source(&mut source, tx, realtime, &control_rx, thread_diagnostics) {
+                error!("Packet capture error: {}", e);
             }
         });
 
-        Some(Self {
+        Self {
             receiver: rx,
             control_tx,
-        })
+            replay_path,
+            diagnostics,
+        }
     }
 
     pub fn drain_events(&mut self) -> Vec<CombatEvent> {
@@ -111,9 +200,34 @@ impl PacketCapture {
         events
     }
 
+    /// Blocking iterator over events; ends when the capture thread exits
+    /// (e.g. when a replay reaches the end of its file)
+    pub fn events(&self) -> mpsc::Iter<'_, CombatEvent> {
+        self.receiver.iter()
+    }
+
     pub fn switch_device(&self, idx: usize) {
         self.control_tx.send(Control::Switch(idx)).ok();
     }
+
+    /// Start or stop recording reassembled frames to a session file
+    pub fn set_recording(&self, enabled: bool) {
+        self.control_tx.send(Control::Record(enabled)).ok();
+    }
+
+    /// Current capture health counters
+    pub fn diagnostics(&self) -> DiagnosticsSnapshot {
+        self.diagnostics.snapshot()
+    }
+
+    /// Capture file being replayed, or None for live capture
+    pub fn replay_path(&self) -> Option<&Path> {
+        self.replay_path.as_deref()
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replay_path.is_some()
+    }
 }
 
 // Auto-select device: prefer default interface, then ethernet, then wifi
@@ -125,12 +239,13 @@ pub fn select_best_device(devices: &[Device]) -> Option<usize> {
     let interfaces = get_interfaces();
 
     // Try for default interface
-    if let Some(default_if) = interfaces.iter().find(|i| i.default) {
-        if default_if.is_up() && has_valid_ipv4_netdev(default_if) {
-            for (i, dev) in devices.iter().enumerate() {
-                if dev.name.contains(&default_if.name) {
-                    return Some(i);
-                }
+    if let Some(default_if) = interfaces.iter().find(|i| i.default)
+        && default_if.is_up()
+        && has_valid_ip_netdev(default_if)
+    {
+        for (i, dev) in devices.iter().enumerate() {
+            if dev.name.contains(&default_if.name) {
+                return Some(i);
             }
         }
     }
@@ -140,21 +255,18 @@ pub fn select_best_device(devices: &[Device]) -> Option<usize> {
     let mut first_wifi: Option<usize> = None;
 
     for (i, dev) in devices.iter().enumerate() {
-        if let Some(iface) = get_netdev_interface(dev, &interfaces) {
-            if iface.is_up() && has_valid_ipv4_netdev(iface) {
-                match iface.if_type {
-                    InterfaceType::Ethernet => {
-                        if first_ethernet.is_none() {
-                            first_ethernet = Some(i);
-                        }
-                    }
-                    InterfaceType::Wireless80211 => {
-                        if first_wifi.is_none() {
-                            first_wifi = Some(i);
-                        }
-                    }
-                    _ => {}
+        if let Some(iface) = get_netdev_interface(dev, &interfaces)
+            && iface.is_up()
+            && has_valid_ip_netdev(iface)
+        {
+            match iface.if_type {
+                InterfaceType::Ethernet if first_ethernet.is_none() => {
+                    first_ethernet = Some(i);
+                }
+                InterfaceType::Wireless80211 if first_wifi.is_none() => {
+                    first_wifi = Some(i);
                 }
+                _ => {}
             }
         }
     }
@@ -163,47 +275,84 @@ pub fn select_best_device(devices: &[Device]) -> Option<usize> {
     first_ethernet.or(first_wifi)
 }
 
-fn capture_loop(
+/// Drive a packet source through TCP reassembly and the parser until it is
+/// exhausted or the owning `PacketCapture` is dropped
+fn run_source(
+    source: &mut dyn PacketSource,
     tx: mpsc::Sender<CombatEvent>,
-    devices: &[Device],
-    device_index: usize,
+    realtime: bool,
     control_rx: &mpsc::Receiver<Control>,
-) -> Result<Option<usize>, Box<dyn std::error::Error>> {
-    use log::info;
-
-    let device = devices.get(device_index).ok_or("Device not found")?.clone();
-    let clean_name = clean_device_name(&device);
-    info!("Starting capture on device: {}", clean_name);
+    diagnostics: Arc<CaptureDiagnostics>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut processor = TcpStreamProcessor::new(tx.clone(), diagnostics.clone());
+    // Survives device switches
+    let mut recording = false;
+    // (first input timestamp, wall clock when it was processed)
+    let mut pace_origin: Option<(SystemTime, Instant)> = None;
+    let mut item_count: u64 = 0;
+    let mut last_stats_poll = Instant::now();
 
-    let mut cap = Capture::from_device(device)?
-        .promisc(true)
-        .snaplen(PCAP_SNAPLEN)
-        .timeout(PCAP_TIMEOUT_MS)
-        .open()?;
+    loop {
+        if last_stats_poll.elapsed() >= PCAP_STATS_INTERVAL {
+            if let Some(stats) = source.stats() {
+                diagnostics.set_pcap_stats(stats.received, stats.dropped, stats.if_dropped);
+            }
+            last_stats_poll = Instant::now();
+        }
 
-    cap.filter("tcp", true)?;
-    info!("Packet capture started (read-only mode)");
-    info!("Filter: tcp");
-    info!("Waiting for Blue Protocol traffic...\n");
+        let link_type = source.link_type();
+        let event = source.next_event()?;
 
-    let mut processor = TcpStreamProcessor::new(tx);
+        if realtime && let Some(timestamp) = event.timestamp() {
+            let (first_ts, started) = *pace_origin.get_or_insert((timestamp, Instant::now()));
+            let offset = timestamp.duration_since(first_ts).unwrap_or_default();
+            let elapsed = started.elapsed();
+            if offset > elapsed {
+                thread::sleep(offset - elapsed);
+            }
+        }
 
-    loop {
-        match cap.next_packet() {
-            Ok(packet) => {
-                processor.process_packet(packet.data);
+        match event {
+            SourceEvent::Packet { data, timestamp } => {
+                processor.process_packet(link_type, data, timestamp);
+                diagnostics
+                    .packets_processed
+                    .fetch_add(1, Ordering::Relaxed);
+                item_count += 1;
             }
-            Err(pcap::Error::TimeoutExpired) => {}
-            Err(e) => {
-                error!("Error capturing packet: {}", e);
+            SourceEvent::Session(SessionRecord::ServerChange {
+                server_endpoint, ..
+            }) => {
+                info!("Recorded server change: {}", server_endpoint);
+                processor.set_server_endpoint(server_endpoint);
             }
+            SourceEvent::Session(SessionRecord::Frame(frame)) => {
+                processor.process_frame(&frame.data, frame.timestamp);
+                item_count += 1;
+            }
+            SourceEvent::Idle => {}
+            SourceEvent::Finished => break,
         }
 
         // Check for control messages
         match control_rx.try_recv() {
-            Ok(Control::Switch(new_idx)) => return Ok(Some(new_idx)),
+            Ok(Control::Switch(new_idx)) => {
+                if source.switch_device(new_idx)? {
+                    processor = TcpStreamProcessor::new(tx.clone(), diagnostics.clone());
+                    processor.set_recording(recording);
+                }
+            }
+            Ok(Control::Record(enabled)) => {
+                if source.is_live() {
+                    processor.set_recording(enabled);
+                    recording = processor.is_recording();
+                }
+            }
             Err(mpsc::TryRecvError::Empty) => {}
-            Err(mpsc::TryRecvError::Disconnected) => return Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => return Ok(()),
         }
     }
+
+    info!("Packet source finished ({} items)", item_count);
+    Ok(())
 }
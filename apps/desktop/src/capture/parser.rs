@@ -2,52 +2,120 @@ use bytes::Bytes;
 use log::{error, info, warn};
 use prost::Message;
 use std::convert::TryFrom;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::capture::diagnostics::CaptureDiagnostics;
 use crate::capture::tcp::ServerEndpoint;
 use crate::models::events::{
-    CombatEvent, DamageHit, DamageTakenHit, EntityPositionUpdate, HealingHit,
-    LocalPlayerPositionUpdate, ModuleDataUpdate, PlayerAbilityScoreUpdate, PlayerAccountInfoUpdate,
-    PlayerClassUpdate, PlayerLineInfoUpdate, PlayerNameUpdate,
+    BodyPartHit, BodyPartStatus, BodyPartUpdate, BuffChange, BuffUpdate, CombatEvent, DamageHit,
+    DamageTakenHit, DisappearReason, EntityDisappearUpdate, EntityPositionUpdate, HateEntry,
+    HateListUpdate, HealingHit, LocalPlayerPositionUpdate, ModuleDataUpdate,
+    PlayerAbilityScoreUpdate, PlayerAccountInfoUpdate, PlayerClassUpdate, PlayerLineInfoUpdate,
+    PlayerNameUpdate, ServerTimeUpdate, SkillCooldown, SkillCooldownUpdate,
 };
 use crate::protocol::constants::{
     ENTER_WORLD_NOTIFY_METHOD_ID, ENTER_WORLD_SERVICE_ID, MessageMethod, MessageType, SERVICE_UUID,
     SOCIAL_NTF_NOTIFY_METHOD_ID, SOCIAL_NTF_SERVICE_ID, entity, packet, packet_layout,
     server_detection,
 };
+use crate::protocol::dirty::{self, DirtySections};
 use crate::protocol::pb::{
-    AoiSyncDelta, AttrCollection, EDamageType, EEntityType, NotifyEnterWorld, NotifySocialData,
-    Position, SceneData, SyncContainerData, SyncNearDeltaInfo, SyncNearEntities, SyncToMeDeltaInfo,
+    ActorBodyPartInfos, AoiSyncDelta, AttrCollection, CharSerialize, EBuffEventType, EDamageType,
+    EDisappearType, EEntityType, ESkillCdType, NotifyEnterWorld, NotifySocialData, Position,
+    SceneData, SyncContainerData, SyncContainerDirtyData, SyncNearDeltaInfo, SyncNearEntities,
+    SyncServerTime, SyncToMeDeltaInfo,
 };
 use crate::utils::constants::is_tracked_mob;
-use std::collections::HashSet;
-use std::sync::{LazyLock, Mutex};
-
-// Last packed line+map emitted from SocialNtf (dedup; see process_notify_social_data).
-static LAST_SOCIAL_SCENE: AtomicU64 = AtomicU64::new(0);
+use std::collections::{HashMap, HashSet, VecDeque};
 
 fn pack_scene(line_id: u32, level_map_id: Option<u32>) -> u64 {
     ((level_map_id.unwrap_or(0) as u64) << 32) | (line_id as u64)
 }
 
-/// Global state for local player tracking
-static mut LOCAL_PLAYER_UUID: i64 = 0;
+/// UUID -> base_id mapping for mobs (populated when mobs first appear).
+/// Oldest mappings are evicted once `entity::MAX_MOB_UUID_MAPPINGS` is reached.
+#[derive(Debug, Default)]
+pub struct MobBaseIds {
+    base_ids: HashMap<i64, u32>,
+    insertion_order: VecDeque<i64>,
+}
 
-/// Global UUID -> base_id mapping for mobs (populated when mobs first appear)
-static mut MOB_UUID_TO_BASE_ID: *mut std::collections::HashMap<i64, u32> = std::ptr::null_mut();
+impl MobBaseIds {
+    pub fn insert(&mut self, uuid: i64, base_id: u32) {
+        if self.base_ids.insert(uuid, base_id).is_some() {
+            return;
+        }
+        self.insertion_order.push_back(uuid);
+        while self.insertion_order.len() > entity::MAX_MOB_UUID_MAPPINGS {
+            if let Some(oldest) = self.insertion_order.pop_front() {
+                self.base_ids.remove(&oldest);
+            }
+        }
+    }
 
-// Used to debug and obtain mob uids
-static LOGGED_NEARBY_MOBS: LazyLock<Mutex<HashSet<u32>>> =
-    LazyLock::new(|| Mutex::new(HashSet::new()));
+    pub fn get(&self, uuid: i64) -> Option<u32> {
+        self.base_ids.get(&uuid).copied()
+    }
 
-fn log_nearby_monster(base_id: u32, position: Option<&crate::models::events::Position>) {
-    let should_log = {
-        let mut seen = LOGGED_NEARBY_MOBS.lock().unwrap();
-        seen.insert(base_id)
-    };
+    pub fn remove(&mut self, uuid: i64) -> Option<u32> {
+        let base_id = self.base_ids.remove(&uuid)?;
+        self.insertion_order.retain(|u| *u != uuid);
+        Some(base_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.base_ids.len()
+    }
 
-    if !should_log {
+    pub fn is_empty(&self) -> bool {
+        self.base_ids.is_empty()
+    }
+}
+
+/// Parser state that spans packets, owned by one TCP stream processor
+#[derive(Debug)]
+pub struct ParserState {
+    /// Capture time of the frame being parsed
+    pub frame_time: SystemTime,
+    /// Local player entity UUID (from SyncToMeDeltaInfo), 0 until known
+    pub local_player_uuid: i64,
+    pub mob_base_ids: MobBaseIds,
+    /// Last packed line+map emitted from SocialNtf (dedup; see process_notify_social_data)
+    last_social_scene: u64,
+    /// Local player's container data: last full SyncContainerData with
+    /// SyncContainerDirtyData deltas applied
+    char_data: Option<CharSerialize>,
+    /// Mob base ids already logged, used to debug and obtain mob uids
+    logged_nearby_mobs: HashSet<u32>,
+}
+
+impl ParserState {
+    pub fn new() -> Self {
+        Self {
+            frame_time: UNIX_EPOCH,
+            local_player_uuid: 0,
+            mob_base_ids: MobBaseIds::default(),
+            last_social_scene: 0,
+            char_data: None,
+            logged_nearby_mobs: HashSet::new(),
+        }
+    }
+}
+
+impl Default for ParserState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn log_nearby_monster(
+    state: &mut ParserState,
+    base_id: u32,
+    position: Option<&crate::models::events::Position>,
+) {
+    if !state.logged_nearby_mobs.insert(base_id) {
         return;
     }
 
@@ -74,17 +142,13 @@ fn log_nearby_monster(base_id: u32, position: Option<&crate::models::events::Pos
 }
 
 pub fn detect_server_in_packet(payload: &[u8], endpoint: &ServerEndpoint) -> bool {
-    if payload.len() == server_detection::LOGIN_RETURN_SIGNATURE_SIZE {
-        if payload.len() >= 20
-            && payload[0..10] == server_detection::LOGIN_RETURN_SIGNATURE[0..10]
-            && payload[14..20] == server_detection::LOGIN_RETURN_SIGNATURE[14..20]
-        {
-            info!(
-                "Detected login return signature from {}",
-                endpoint.to_string()
-            );
-            return true;
-        }
+    if payload.len() == server_detection::LOGIN_RETURN_SIGNATURE_SIZE
+        && payload.len() >= 20
+        && payload[0..10] == server_detection::LOGIN_RETURN_SIGNATURE[0..10]
+        && payload[14..20] == server_detection::LOGIN_RETURN_SIGNATURE[14..20]
+    {
+        info!("Detected login return signature from {}", endpoint);
+        return true;
     }
 
     if payload.len() > 10 && payload[4] == 0 {
@@ -114,10 +178,7 @@ pub fn detect_server_in_packet(payload: &[u8], endpoint: &ServerEndpoint) -> boo
                 if data[signature_start..signature_start + server_detection::SERVER_SIGNATURE.len()]
                     == server_detection::SERVER_SIGNATURE[..]
                 {
-                    info!(
-                        "Detected server signature in packet from {}",
-                        endpoint.to_string()
-                    );
+                    info!("Detected server signature in packet from {}", endpoint);
                     return true;
                 }
             }
@@ -130,12 +191,23 @@ pub fn detect_server_in_packet(payload: &[u8], endpoint: &ServerEndpoint) -> boo
 }
 
 /// Process a single Blue Protocol packet (recursively unwraps FrameDown packets)
-pub fn process_bp_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>) {
-    process_bp_packet_recursive(data, tx, 0);
+pub fn process_bp_packet(
+    data: &[u8],
+    state: &mut ParserState,
+    tx: &mpsc::Sender<CombatEvent>,
+    diagnostics: &CaptureDiagnostics,
+) {
+    process_bp_packet_recursive(data, state, tx, diagnostics, 0);
 }
 
 /// Recursively process BP packets (handles FrameDown unwrapping)
-fn process_bp_packet_recursive(data: &[u8], tx: &mpsc::Sender<CombatEvent>, depth: usize) {
+fn process_bp_packet_recursive(
+    data: &[u8],
+    state: &mut ParserState,
+    tx: &mpsc::Sender<CombatEvent>,
+    diagnostics: &CaptureDiagnostics,
+    depth: usize,
+) {
     if data.len() < 6 {
         return;
     }
@@ -146,7 +218,7 @@ fn process_bp_packet_recursive(data: &[u8], tx: &mpsc::Sender<CombatEvent>, dept
 
     match msg_type {
         MessageType::Notify => {
-            process_notify_packet(data, tx, is_compressed);
+            process_notify_packet(data, state, tx, diagnostics, is_compressed);
         }
         MessageType::Return => {}
         MessageType::FrameDown => {
@@ -160,6 +232,7 @@ fn process_bp_packet_recursive(data: &[u8], tx: &mpsc::Sender<CombatEvent>, dept
                 match zstd::decode_all(nested_data) {
                     Ok(decompressed) => decompressed,
                     Err(_) => {
+                        diagnostics.zstd_failures.fetch_add(1, Ordering::Relaxed);
                         return;
                     }
                 }
@@ -183,7 +256,7 @@ fn process_bp_packet_recursive(data: &[u8], tx: &mpsc::Sender<CombatEvent>, dept
 
                 let nested_packet = &final_nested_data[pos..pos + nested_packet_size];
                 if depth < 10 {
-                    process_bp_packet_recursive(nested_packet, tx, depth + 1);
+                    process_bp_packet_recursive(nested_packet, state, tx, diagnostics, depth + 1);
                 }
 
                 pos += nested_packet_size;
@@ -193,7 +266,13 @@ fn process_bp_packet_recursive(data: &[u8], tx: &mpsc::Sender<CombatEvent>, dept
     }
 }
 
-fn process_notify_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>, is_compressed: bool) {
+fn process_notify_packet(
+    data: &[u8],
+    state: &mut ParserState,
+    tx: &mpsc::Sender<CombatEvent>,
+    diagnostics: &CaptureDiagnostics,
+    is_compressed: bool,
+) {
     if data.len() < 22 {
         return;
     }
@@ -214,6 +293,7 @@ fn process_notify_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>, is_compres
         match zstd::decode_all(payload) {
             Ok(decompressed) => decompressed,
             Err(_) => {
+                diagnostics.zstd_failures.fetch_add(1, Ordering::Relaxed);
                 return;
             }
         }
@@ -223,12 +303,15 @@ fn process_notify_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>, is_compres
 
     if service_uuid == SOCIAL_NTF_SERVICE_ID {
         if method_id == SOCIAL_NTF_NOTIFY_METHOD_ID {
-            match process_notify_social_data(&final_payload) {
+            match process_notify_social_data(state, &final_payload) {
                 Ok(Some(event)) => {
                     let _ = tx.send(event);
                 }
                 Ok(None) => {}
-                Err(e) => warn!("SocialNtf decode failed: {}", e),
+                Err(e) => {
+                    diagnostics.record_decode_failure("NotifySocialData");
+                    warn!("SocialNtf decode failed: {}", e);
+                }
             }
         }
         return;
@@ -241,7 +324,10 @@ fn process_notify_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>, is_compres
                     let _ = tx.send(event);
                 }
                 Ok(None) => {}
-                Err(e) => warn!("NotifyEnterWorld decode failed: {}", e),
+                Err(e) => {
+                    diagnostics.record_decode_failure("NotifyEnterWorld");
+                    warn!("NotifyEnterWorld decode failed: {}", e);
+                }
             }
         }
         return;
@@ -253,46 +339,52 @@ fn process_notify_packet(data: &[u8], tx: &mpsc::Sender<CombatEvent>, is_compres
 
     let method = match MessageMethod::from_u32(method_id) {
         Some(m) => m,
-        None => return,
+        None => {
+            diagnostics.record_unknown_method(method_id);
+            return;
+        }
     };
 
-    match method {
-        MessageMethod::SyncNearDeltaInfo => {
-            if let Ok(events) = process_sync_near_delta(&final_payload) {
-                for event in events {
-                    let _ = tx.send(event);
-                }
-            }
-        }
+    let result: Result<Vec<CombatEvent>, Box<dyn std::error::Error>> = match method {
+        MessageMethod::SyncNearDeltaInfo => process_sync_near_delta(state, &final_payload),
         MessageMethod::SyncToMeDeltaInfo => {
-            if let Ok(events) = process_sync_to_me_delta(&final_payload) {
-                for event in events {
-                    let _ = tx.send(event);
-                }
+            process_sync_to_me_delta(state, &final_payload).map_err(|e| e.into())
+        }
+        MessageMethod::SyncNearEntities => process_sync_near_entities(state, &final_payload),
+        MessageMethod::SyncContainerData => {
+            let result = process_sync_container_data(state, &final_payload);
+            if let Err(e) = &result {
+                warn!("SyncContainerData: {}", e);
             }
+            result
         }
-        MessageMethod::SyncNearEntities => {
-            if let Ok(events) = process_sync_near_entities(&final_payload) {
-                for event in events {
-                    let _ = tx.send(event);
-                }
+        MessageMethod::SyncContainerDirtyData => {
+            let result = process_sync_container_dirty_data(state, &final_payload, diagnostics);
+            if let Err(e) = &result {
+                warn!("SyncContainerDirtyData: {}", e);
             }
+            result
         }
-        MessageMethod::SyncContainerData => match process_sync_container_data(&final_payload) {
-            Ok(events) => {
-                for event in events {
-                    let _ = tx.send(event);
-                }
+        MessageMethod::SyncServerTime => {
+            process_sync_server_time(state, &final_payload).map_err(|e| e.into())
+        }
+    };
+
+    match result {
+        Ok(events) => {
+            for event in events {
+                let _ = tx.send(event);
             }
-            Err(e) => warn!("SyncContainerData: {}", e),
-        },
-        MessageMethod::SyncContainerDirtyData => {}
-        MessageMethod::SyncServerTime => {}
+        }
+        Err(_) => diagnostics.record_decode_failure(method.name()),
     }
 }
 
 /// Process SyncNearDeltaInfo (method_id=45) - damage/healing data and position updates
-fn process_sync_near_delta(payload: &[u8]) -> Result<Vec<CombatEvent>, Box<dyn std::error::Error>> {
+fn process_sync_near_delta(
+    state: &mut ParserState,
+    payload: &[u8],
+) -> Result<Vec<CombatEvent>, Box<dyn std::error::Error>> {
     let delta_info = SyncNearDeltaInfo::decode(Bytes::copy_from_slice(payload))?;
     let mut events = Vec::new();
 
@@ -303,118 +395,260 @@ fn process_sync_near_delta(payload: &[u8]) -> Result<Vec<CombatEvent>, Box<dyn s
 
         if let Some(attrs) = &delta.attrs {
             for attr in &attrs.attrs {
-                if attr.id == crate::protocol::constants::AttrType::AttrId as i32 {
-                    if let Ok(id) = decode_protobuf_int32(&attr.raw_data) {
-                        monster_base_id = Some(id as u32);
-                        break;
-                    }
+                if attr.id == crate::protocol::constants::AttrType::AttrId as i32
+                    && let Ok(id) = decode_protobuf_int32(&attr.raw_data)
+                {
+                    monster_base_id = Some(id as u32);
+                    break;
                 }
             }
         }
 
         let base_id = if let Some(id) = monster_base_id {
-            unsafe {
-                if MOB_UUID_TO_BASE_ID.is_null() {
-                    let map = Box::into_raw(Box::new(std::collections::HashMap::new()));
-                    MOB_UUID_TO_BASE_ID = map;
-                }
-                if !MOB_UUID_TO_BASE_ID.is_null() {
-                    (*MOB_UUID_TO_BASE_ID).insert(uuid, id);
-                }
-            }
+            state.mob_base_ids.insert(uuid, id);
             Some(id)
         } else {
-            unsafe {
-                if !MOB_UUID_TO_BASE_ID.is_null() {
-                    (*MOB_UUID_TO_BASE_ID).get(&uuid).copied()
-                } else {
-                    None
-                }
-            }
+            state.mob_base_ids.get(uuid)
         };
 
-        if let Some(base_id) = base_id {
-            if let Some(attrs) = &delta.attrs {
-                let position = extract_position_from_attrs(&Some(attrs.clone()));
-                log_nearby_monster(base_id, position.as_ref());
-
-                if is_tracked_mob(base_id) {
-                    let (current_hp, max_hp) = extract_hp_from_attrs(&Some(attrs.clone()));
-
-                    // (mob can take damage without moving)
-                    let has_position = position.is_some();
-                    let has_hp = current_hp.is_some() || max_hp.is_some();
-
-                    if has_position || has_hp {
-                        if let Some(pos) = position {
-                            events.push(CombatEvent::EntityPosition(EntityPositionUpdate {
-                                uuid,
-                                entity_type: crate::models::events::EntityType::Monster,
-                                position: pos,
-                                mob_base_id: Some(base_id),
-                                current_hp,
-                                max_hp,
-                            }));
-                        } else if has_hp {
-                            // HP-only update (sentinel position)
-                            events.push(CombatEvent::EntityPosition(EntityPositionUpdate {
-                                uuid,
-                                entity_type: crate::models::events::EntityType::Monster,
-                                position: crate::models::events::Position {
-                                    x: f32::NEG_INFINITY,
-                                    y: f32::NEG_INFINITY,
-                                    z: f32::NEG_INFINITY,
-                                },
-                                mob_base_id: Some(base_id),
-                                current_hp,
-                                max_hp,
-                            }));
-                        }
+        if let Some(base_id) = base_id
+            && let Some(attrs) = &delta.attrs
+        {
+            let position = extract_position_from_attrs(&Some(attrs.clone()));
+            log_nearby_monster(state, base_id, position.as_ref());
+
+            if is_tracked_mob(base_id) {
+                let (current_hp, max_hp) = extract_hp_from_attrs(&Some(attrs.clone()));
+
+                // (mob can take damage without moving)
+                let has_position = position.is_some();
+                let has_hp = current_hp.is_some() || max_hp.is_some();
+
+                if has_position || has_hp {
+                    if let Some(pos) = position {
+                        events.push(CombatEvent::EntityPosition(EntityPositionUpdate {
+                            uuid,
+                            entity_type: crate::models::events::EntityType::Monster,
+                            position: pos,
+                            mob_base_id: Some(base_id),
+                            current_hp,
+                            max_hp,
+                        }));
+                    } else if has_hp {
+                        // HP-only update (sentinel position)
+                        events.push(CombatEvent::EntityPosition(EntityPositionUpdate {
+                            uuid,
+                            entity_type: crate::models::events::EntityType::Monster,
+                            position: crate::models::events::Position {
+                                x: f32::NEG_INFINITY,
+                                y: f32::NEG_INFINITY,
+                                z: f32::NEG_INFINITY,
+                            },
+                            mob_base_id: Some(base_id),
+                            current_hp,
+                            max_hp,
+                        }));
                     }
                 }
             }
         }
 
-        events.extend(extract_combat_events_from_aoi_delta(delta));
+        events.extend(extract_combat_events_from_aoi_delta(
+            delta,
+            &state.mob_base_ids,
+        ));
+        events.extend(extract_buff_events_from_aoi_delta(delta));
+        if let Some(infos) = &delta.body_part_infos {
+            events.extend(body_part_event(uuid, infos, state));
+        }
     }
 
     Ok(events)
 }
 
+/// Process SyncServerTime (method_id=43) - game server clock sample
+fn process_sync_server_time(
+    state: &ParserState,
+    payload: &[u8],
+) -> Result<Vec<CombatEvent>, prost::DecodeError> {
+    let sync = SyncServerTime::decode(Bytes::copy_from_slice(payload))?;
+    if sync.server_milliseconds <= 0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![CombatEvent::ServerTime(ServerTimeUpdate {
+        client_ms: sync.client_milliseconds,
+        server_ms: sync.server_milliseconds,
+        received_ms: received_ms(state),
+    })])
+}
+
+/// Capture time of the frame being parsed, in Unix milliseconds
+fn received_ms(state: &ParserState) -> i64 {
+    state
+        .frame_time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
 /// Process SyncToMeDeltaInfo (method_id=46) - local player data and combat events
-fn process_sync_to_me_delta(payload: &[u8]) -> Result<Vec<CombatEvent>, prost::DecodeError> {
+fn process_sync_to_me_delta(
+    state: &mut ParserState,
+    payload: &[u8],
+) -> Result<Vec<CombatEvent>, prost::DecodeError> {
     let delta_info = SyncToMeDeltaInfo::decode(Bytes::copy_from_slice(payload))?;
     let mut events = Vec::new();
 
     if let Some(delta) = delta_info.delta_info.as_ref() {
         let uuid = delta.uuid;
         if uuid != 0 {
-            unsafe {
-                if uuid != LOCAL_PLAYER_UUID {
-                    LOCAL_PLAYER_UUID = uuid;
-                }
-            }
+            state.local_player_uuid = uuid;
         }
 
         if let Some(base_delta) = &delta.base_delta {
-            if let Some(attrs) = &base_delta.attrs {
-                if let Some(position) = extract_position_from_attrs(&Some(attrs.clone())) {
-                    events.push(CombatEvent::LocalPlayerPosition(
-                        LocalPlayerPositionUpdate { position },
-                    ));
-                }
+            if let Some(attrs) = &base_delta.attrs
+                && let Some(position) = extract_position_from_attrs(&Some(attrs.clone()))
+            {
+                events.push(CombatEvent::LocalPlayerPosition(
+                    LocalPlayerPositionUpdate { position },
+                ));
             }
 
-            events.extend(extract_combat_events_from_aoi_delta(base_delta));
+            events.extend(extract_combat_events_from_aoi_delta(
+                base_delta,
+                &state.mob_base_ids,
+            ));
+            events.extend(extract_buff_events_from_aoi_delta(base_delta));
+        }
+
+        if !delta.sync_hate_ids.is_empty() {
+            let entries = delta
+                .sync_hate_ids
+                .iter()
+                .map(|&uuid| HateEntry {
+                    uuid,
+                    base_id: state.mob_base_ids.get(uuid),
+                })
+                .collect();
+            events.push(CombatEvent::HateList(HateListUpdate { entries }));
+        }
+
+        if !delta.sync_skill_c_ds.is_empty() {
+            let cooldowns = delta
+                .sync_skill_c_ds
+                .iter()
+                .filter(|cd| cd.skill_level_id != 0)
+                .map(|cd| SkillCooldown {
+                    skill_level_id: cd.skill_level_id,
+                    begin_server_ms: cd.begin_time,
+                    // valid_cd_time is the duration after cooldown reduction
+                    duration_ms: if cd.valid_cd_time > 0 {
+                        cd.valid_cd_time
+                    } else {
+                        cd.duration
+                    },
+                    is_charge: cd.skill_cd_type == ESkillCdType::SkillCdCharge as i32,
+                })
+                .collect();
+            events.push(CombatEvent::SkillCooldowns(SkillCooldownUpdate {
+                cooldowns,
+            }));
         }
     }
 
     Ok(events)
 }
 
+/// Extract buff changes on players from AoiSyncDelta buff_infos/buff_effect
+fn extract_buff_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent> {
+    let mut events = Vec::new();
+
+    if let Some(sync) = &delta.buff_infos {
+        let host_uuid = if sync.uuid != 0 {
+            sync.uuid
+        } else {
+            delta.uuid
+        };
+        if entity::is_player(host_uuid) {
+            for info in &sync.buff_infos {
+                events.push(CombatEvent::Buff(BuffUpdate {
+                    player_uid: entity::get_player_uid(host_uuid),
+                    buff_uuid: info.buff_uuid,
+                    change: BuffChange::Applied {
+                        base_id: info.base_id,
+                        layer: info.layer.max(1),
+                        duration_ms: info.duration,
+                        caster_uuid: info.fire_uuid,
+                    },
+                }));
+            }
+        }
+    }
+
+    if let Some(sync) = &delta.buff_effect {
+        let host_uuid = if sync.uuid != 0 {
+            sync.uuid
+        } else {
+            delta.uuid
+        };
+        if entity::is_player(host_uuid) {
+            for effect in &sync.buff_effects {
+                let change = match EBuffEventType::try_from(effect.r#type) {
+                    Ok(EBuffEventType::BuffEventRemove) => BuffChange::Removed,
+                    Ok(EBuffEventType::BuffEventReplace) => BuffChange::Refreshed,
+                    Ok(EBuffEventType::BuffEventStackLayer) => BuffChange::LayerAdded,
+                    Ok(EBuffEventType::BuffEventRemoveLayer) => BuffChange::LayerRemoved,
+                    // AddTo is followed by a BuffInfo; timer ticks change nothing
+                    _ => continue,
+                };
+                events.push(CombatEvent::Buff(BuffUpdate {
+                    player_uid: entity::get_player_uid(host_uuid),
+                    buff_uuid: effect.buff_uuid,
+                    change,
+                }));
+            }
+        }
+    }
+
+    events
+}
+
+/// Part HP/break state of a non-player entity. `uuid` is used when the
+/// message does not carry its own.
+fn body_part_event(
+    uuid: i64,
+    infos: &ActorBodyPartInfos,
+    state: &ParserState,
+) -> Option<CombatEvent> {
+    let uuid = if infos.uuid != 0 { infos.uuid } else { uuid };
+    if infos.infos.is_empty() || entity::is_player(uuid) {
+        return None;
+    }
+    let parts = infos
+        .infos
+        .iter()
+        .map(|info| BodyPartStatus {
+            part_id: info.part_id,
+            hp: info.hp,
+            max_hp: info.max_hp,
+            state: info.state,
+        })
+        .collect();
+    Some(CombatEvent::BodyParts(BodyPartUpdate {
+        uuid,
+        base_id: state.mob_base_ids.get(uuid),
+        parts,
+        received_ms: received_ms(state),
+    }))
+}
+
 /// Extract combat events from AoiSyncDelta (proto: AoiSyncDelta)
 /// Processes all damage entries in skill_effects.damages[]
-fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent> {
+fn extract_combat_events_from_aoi_delta(
+    delta: &AoiSyncDelta,
+    mob_base_ids: &MobBaseIds,
+) -> Vec<CombatEvent> {
     let skill_effects = match delta.skill_effects.as_ref() {
         Some(se) => se,
         None => {
@@ -444,6 +678,18 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
             continue;
         };
 
+        // Entity that actually dealt the hit (summon, pet, turret or the owner)
+        let source_uuid = if damage_info.attacker_uuid != 0 {
+            damage_info.attacker_uuid
+        } else {
+            attacker_uuid
+        };
+        let source_base_id = if source_uuid != attacker_uuid {
+            mob_base_ids.get(source_uuid)
+        } else {
+            None
+        };
+
         // Prefer lucky damage value if available
         let damage = if damage_info.lucky_value != 0 {
             damage_info.lucky_value
@@ -452,6 +698,7 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
         };
 
         let hp_lessen = damage_info.hp_lessen_value;
+        let shield_lessen = damage_info.shield_lessen_value;
 
         let damage_type_enum =
             EDamageType::try_from(damage_info.r#type).unwrap_or(EDamageType::Normal);
@@ -460,13 +707,40 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
         let is_dead = damage_info.is_dead;
         let is_heal = damage_type_enum == EDamageType::Heal;
         let is_miss_type = damage_type_enum == EDamageType::Miss;
+        let is_absorbed = damage_type_enum == EDamageType::Absorbed;
+
+        // HP plus shield actually removed; whatever a killing blow had left over is overkill
+        let effective_damage = if hp_lessen > 0 || shield_lessen > 0 {
+            hp_lessen + shield_lessen
+        } else if damage_info.actual_value > 0 {
+            damage_info.actual_value
+        } else {
+            damage
+        }
+        .min(damage);
+        let overkill = if is_dead {
+            damage - effective_damage
+        } else {
+            0
+        };
 
         let target_uuid_raw = delta.uuid;
         let target_uuid = entity::get_player_uid(target_uuid_raw);
         let is_target_player = entity::is_player(target_uuid_raw);
+        let target_base_id = if is_target_player {
+            None
+        } else {
+            mob_base_ids.get(target_uuid_raw)
+        };
 
         let is_player_attacker = entity::is_player(attacker_uuid);
         let player_uid = entity::get_player_uid(attacker_uuid);
+        // Monster (or summon) base id of whoever hit a player, for death recaps
+        let hitter_base_id = if entity::is_player(source_uuid) {
+            None
+        } else {
+            mob_base_ids.get(source_uuid)
+        };
 
         // Extract crit/lucky flags
         let is_crit = (damage_info.type_flag & 1) == 1;
@@ -482,8 +756,12 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
                 events.push(CombatEvent::DamageTaken(DamageTakenHit {
                     player_uid: target_uuid,
                     hp_lessen: 0,
+                    shield_lessen: 0,
                     is_miss: true,
                     is_dead,
+                    attacker_uuid: source_uuid,
+                    attacker_base_id: hitter_base_id,
+                    skill_id,
                 }));
             }
             continue;
@@ -493,6 +771,8 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
             if is_player_attacker && is_target_player {
                 events.push(CombatEvent::Healing(HealingHit {
                     player_uid,
+                    target_uid: target_uuid,
+                    skill_id,
                     healing: damage,
                     is_crit,
                     is_lucky,
@@ -501,22 +781,63 @@ fn extract_combat_events_from_aoi_delta(delta: &AoiSyncDelta) -> Vec<CombatEvent
             continue;
         }
 
+        if is_player_attacker && !is_target_player {
+            for part in damage_info.part_infos.iter().filter(|p| p.damage_val > 0) {
+                events.push(CombatEvent::BodyPartHit(BodyPartHit {
+                    player_uid,
+                    target_uuid: target_uuid_raw,
+                    target_base_id,
+                    part_id: part.part_id,
+                    damage: part.damage_val,
+                }));
+            }
+        }
+
         if is_player_attacker {
             events.push(CombatEvent::Damage(DamageHit {
                 player_uid,
+                skill_id,
                 damage,
                 is_crit,
                 is_lucky,
+                attacker_uuid: source_uuid,
+                attacker_entity_type: entity::get_entity_type(source_uuid),
+                attacker_base_id: source_base_id,
+                damage_source: damage_info.damage_source,
+                property: damage_info.property,
+                damage_mode: damage_info.damage_mode,
+                effective_damage,
+                overkill,
+                target_uuid: target_uuid_raw,
+                target_base_id,
+                is_dead,
             }));
         }
 
         if is_target_player {
-            let actual_hp_lessen = if hp_lessen > 0 { hp_lessen } else { damage };
+            let (actual_hp_lessen, absorbed) = if is_absorbed {
+                (
+                    0,
+                    if shield_lessen > 0 {
+                        shield_lessen
+                    } else {
+                        damage
+                    },
+                )
+            } else if hp_lessen > 0 || shield_lessen > 0 {
+                (hp_lessen, shield_lessen)
+            } else {
+                (damage, 0)
+            };
             events.push(CombatEvent::DamageTaken(DamageTakenHit {
                 player_uid: target_uuid,
                 hp_lessen: actual_hp_lessen,
+                shield_lessen: absorbed,
                 is_miss: false,
                 is_dead,
+                attacker_uuid: source_uuid,
+                attacker_base_id: hitter_base_id,
+                skill_id,
             }));
         }
     }
@@ -541,10 +862,10 @@ fn extract_hp_from_attrs(attrs: &Option<AttrCollection>) -> (Option<u64>, Option
             if let Ok(value) = decode_protobuf_int64(&attr.raw_data) {
                 current_hp = Some(value as u64);
             }
-        } else if attr.id == crate::protocol::constants::AttrType::AttrMaxHp as i32 {
-            if let Ok(value) = decode_protobuf_int64(&attr.raw_data) {
-                max_hp = Some(value as u64);
-            }
+        } else if attr.id == crate::protocol::constants::AttrType::AttrMaxHp as i32
+            && let Ok(value) = decode_protobuf_int64(&attr.raw_data)
+        {
+            max_hp = Some(value as u64);
         }
     }
 
@@ -559,17 +880,17 @@ fn extract_position_from_attrs(
 
     // AttrPos = 52: Position protobuf
     for attr in &attrs.attrs {
-        if attr.id == 52 {
-            if let Ok(pb_position) = Position::decode(Bytes::copy_from_slice(&attr.raw_data)) {
-                let x_abs = pb_position.x.abs();
-                let z_abs = pb_position.z.abs();
-                if x_abs < 100000.0 && z_abs < 100000.0 {
-                    return Some(crate::models::events::Position {
-                        x: pb_position.x,
-                        y: pb_position.y,
-                        z: pb_position.z,
-                    });
-                }
+        if attr.id == 52
+            && let Ok(pb_position) = Position::decode(Bytes::copy_from_slice(&attr.raw_data))
+        {
+            let x_abs = pb_position.x.abs();
+            let z_abs = pb_position.z.abs();
+            if x_abs < 100000.0 && z_abs < 100000.0 {
+                return Some(crate::models::events::Position {
+                    x: pb_position.x,
+                    y: pb_position.y,
+                    z: pb_position.z,
+                });
             }
         }
     }
@@ -579,6 +900,7 @@ fn extract_position_from_attrs(
 
 /// Process SyncNearEntities (method_id=6) - entity appearance/disappearance data
 fn process_sync_near_entities(
+    state: &mut ParserState,
     payload: &[u8],
 ) -> Result<Vec<CombatEvent>, Box<dyn std::error::Error>> {
     let sync_info = SyncNearEntities::decode(Bytes::copy_from_slice(payload))?;
@@ -597,37 +919,33 @@ fn process_sync_near_entities(
             if let Some(attrs) = &entity.attrs {
                 for attr in &attrs.attrs {
                     if attr.id == crate::protocol::constants::AttrType::AttrName as i32 {
-                        if let Ok(player_name) = decode_protobuf_string(&attr.raw_data) {
-                            if !player_name.is_empty() {
-                                events.push(CombatEvent::PlayerName(PlayerNameUpdate {
-                                    player_uid,
-                                    name: player_name,
-                                }));
-                            }
+                        if let Ok(player_name) = decode_protobuf_string(&attr.raw_data)
+                            && !player_name.is_empty()
+                        {
+                            events.push(CombatEvent::PlayerName(PlayerNameUpdate {
+                                player_uid,
+                                name: player_name,
+                            }));
                         }
                     } else if attr.id
                         == crate::protocol::constants::AttrType::AttrProfessionId as i32
                     {
-                        if let Ok(class_id) = decode_protobuf_int32(&attr.raw_data) {
-                            if class_id > 0 {
-                                events.push(CombatEvent::PlayerClass(PlayerClassUpdate {
-                                    player_uid,
-                                    class_id,
-                                }));
-                            }
+                        if let Ok(class_id) = decode_protobuf_int32(&attr.raw_data)
+                            && class_id > 0
+                        {
+                            events.push(CombatEvent::PlayerClass(PlayerClassUpdate {
+                                player_uid,
+                                class_id,
+                            }));
                         }
                     } else if attr.id == crate::protocol::constants::AttrType::AttrFightPoint as i32
+                        && let Ok(ability_score) = decode_protobuf_int32(&attr.raw_data)
+                        && ability_score > 0
                     {
-                        if let Ok(ability_score) = decode_protobuf_int32(&attr.raw_data) {
-                            if ability_score > 0 {
-                                events.push(CombatEvent::PlayerAbilityScore(
-                                    PlayerAbilityScoreUpdate {
-                                        player_uid,
-                                        ability_score,
-                                    },
-                                ));
-                            }
-                        }
+                        events.push(CombatEvent::PlayerAbilityScore(PlayerAbilityScoreUpdate {
+                            player_uid,
+                            ability_score,
+                        }));
                     }
                 }
             }
@@ -650,12 +968,12 @@ fn process_sync_near_entities(
 
             if let Some(attrs) = &entity.attrs {
                 for attr in &attrs.attrs {
-                    if attr.id == crate::protocol::constants::AttrType::AttrId as i32 {
-                        if let Ok(id) = decode_protobuf_int32(&attr.raw_data) {
-                            let id = id as u32;
-                            monster_base_id = Some(id);
-                            break;
-                        }
+                    if attr.id == crate::protocol::constants::AttrType::AttrId as i32
+                        && let Ok(id) = decode_protobuf_int32(&attr.raw_data)
+                    {
+                        let id = id as u32;
+                        monster_base_id = Some(id);
+                        break;
                     }
                 }
             }
@@ -678,16 +996,10 @@ fn process_sync_near_entities(
             }
 
             let position = extract_position_from_attrs(&entity.attrs);
-            log_nearby_monster(monster_base_id, position.as_ref());
-
-            unsafe {
-                if MOB_UUID_TO_BASE_ID.is_null() {
-                    let map = Box::into_raw(Box::new(std::collections::HashMap::new()));
-                    MOB_UUID_TO_BASE_ID = map;
-                }
-                if !MOB_UUID_TO_BASE_ID.is_null() {
-                    (*MOB_UUID_TO_BASE_ID).insert(entity.uuid, monster_base_id);
-                }
+            log_nearby_monster(state, monster_base_id, position.as_ref());
+            state.mob_base_ids.insert(entity.uuid, monster_base_id);
+            if let Some(infos) = &entity.body_part_infos {
+                events.extend(body_part_event(entity.uuid, infos, state));
             }
 
             if is_tracked_mob(monster_base_id) {
@@ -706,11 +1018,28 @@ fn process_sync_near_entities(
         }
     }
 
+    for entity in &sync_info.disappear {
+        let reason = match EDisappearType::try_from(entity.r#type) {
+            Ok(EDisappearType::DisappearDead) => DisappearReason::Died,
+            Ok(EDisappearType::DisappearDestroy) => DisappearReason::Destroyed,
+            Ok(EDisappearType::DisappearTransferLeave)
+            | Ok(EDisappearType::DisappearTransferPassLineLeave) => DisappearReason::Transferred,
+            _ => DisappearReason::LeftRange,
+        };
+        let mob_base_id = state.mob_base_ids.remove(entity.uuid);
+        events.push(CombatEvent::EntityDisappear(EntityDisappearUpdate {
+            uuid: entity.uuid,
+            reason,
+            mob_base_id,
+        }));
+    }
+
     Ok(events)
 }
 
 /// Process SyncContainerData (method_id=21) - local player's full container data
 fn process_sync_container_data(
+    state: &mut ParserState,
     payload: &[u8],
 ) -> Result<Vec<CombatEvent>, Box<dyn std::error::Error>> {
     let sync_data = match SyncContainerData::decode(Bytes::copy_from_slice(payload)) {
@@ -725,14 +1054,73 @@ fn process_sync_container_data(
     };
     let mut events = Vec::new();
 
-    if let Some(v_data) = &sync_data.v_data {
-        let player_uid = v_data.char_id;
-        if player_uid == 0 {
-            warn!("[SyncContainerData] player_uid is 0, returning early");
-            return Ok(events);
-        }
+    let Some(v_data) = sync_data.v_data else {
+        return Ok(events);
+    };
+
+    if v_data.char_id == 0 {
+        warn!("[SyncContainerData] player_uid is 0, returning early");
+        return Ok(events);
+    }
 
-        if let Some(char_base) = &v_data.char_base {
+    let all_sections = DirtySections {
+        char_base: true,
+        scene_data: true,
+        modules: true,
+        profession: true,
+        ..Default::default()
+    };
+    push_char_serialize_events(&v_data, all_sections, &mut events);
+    state.char_data = Some(v_data);
+
+    Ok(events)
+}
+
+/// Process SyncContainerDirtyData (method_id=22) - incremental changes to the
+/// container data last received in SyncContainerData
+fn process_sync_container_dirty_data(
+    state: &mut ParserState,
+    payload: &[u8],
+    diagnostics: &CaptureDiagnostics,
+) -> Result<Vec<CombatEvent>, Box<dyn std::error::Error>> {
+    let dirty = SyncContainerDirtyData::decode(Bytes::copy_from_slice(payload))?;
+    let mut events = Vec::new();
+
+    if dirty.v_data.is_empty() {
+        return Ok(events);
+    }
+
+    // Deltas before the first full sync still carry line changes, but modules
+    // decoded from a partial model would replace the real list
+    let has_full_sync = state.char_data.is_some();
+    let char_data = state.char_data.get_or_insert_with(CharSerialize::default);
+    let mut sections = dirty::apply_dirty_data(char_data, &dirty.v_data)?;
+    if sections.truncated_objects > 0 {
+        diagnostics
+            .dirty_truncated_objects
+            .fetch_add(sections.truncated_objects as u64, Ordering::Relaxed);
+    }
+    if !has_full_sync {
+        sections.modules = false;
+    }
+    if sections.any() {
+        push_char_serialize_events(char_data, sections, &mut events);
+    }
+
+    Ok(events)
+}
+
+/// Events derived from the changed sections of the local player's CharSerialize
+fn push_char_serialize_events(
+    v_data: &CharSerialize,
+    sections: DirtySections,
+    events: &mut Vec<CombatEvent>,
+) {
+    let player_uid = v_data.char_id;
+
+    // Player-keyed events need the uid from a full sync
+    if player_uid != 0 {
+        if let Some(char_base) = v_data.char_base.as_ref().filter(|_| sections.char_base) {
             if !char_base.name.is_empty() {
                 events.push(CombatEvent::PlayerName(PlayerNameUpdate {
                     player_uid,
@@ -755,39 +1143,33 @@ fn process_sync_container_data(
             }
         }
 
-        if let Some(profession_list) = &v_data.profession_list {
-            if profession_list.cur_profession_id > 0 {
-                events.push(CombatEvent::PlayerClass(PlayerClassUpdate {
-                    player_uid,
-                    class_id: profession_list.cur_profession_id,
-                }));
-            }
-        }
-
-        if let Some(scene_data) = &v_data.scene_data {
-            if let Some(update) = player_line_info_from_scene_data(scene_data) {
-                events.push(CombatEvent::PlayerLineInfo(update));
-            }
+        if let Some(profession_list) = v_data
+            .profession_list
+            .as_ref()
+            .filter(|_| sections.profession)
+            && profession_list.cur_profession_id > 0
+        {
+            events.push(CombatEvent::PlayerClass(PlayerClassUpdate {
+                player_uid,
+                class_id: profession_list.cur_profession_id,
+            }));
         }
+    }
 
-        if let Ok(extracted_modules) = crate::utils::modules::extract_modules(&sync_data) {
-            if !extracted_modules.is_empty() {
-                events.push(CombatEvent::ModuleData(ModuleDataUpdate {
-                    modules: extracted_modules,
-                }));
-            }
-        }
-    } else {
-        if let Ok(extracted_modules) = crate::utils::modules::extract_modules(&sync_data) {
-            if !extracted_modules.is_empty() {
-                events.push(CombatEvent::ModuleData(ModuleDataUpdate {
-                    modules: extracted_modules,
-                }));
-            }
-        }
+    if let Some(scene_data) = v_data.scene_data.as_ref().filter(|_| sections.scene_data)
+        && let Some(update) = player_line_info_from_scene_data(scene_data)
+    {
+        events.push(CombatEvent::PlayerLineInfo(update));
     }
 
-    Ok(events)
+    if sections.modules
+        && let Ok(extracted_modules) = crate::utils::modules::extract_modules_from_char(v_data)
+        && !extracted_modules.is_empty()
+    {
+        events.push(CombatEvent::ModuleData(ModuleDataUpdate {
+            modules: extracted_modules,
+        }));
+    }
 }
 
 fn player_line_info_from_scene_data(scene_data: &SceneData) -> Option<PlayerLineInfoUpdate> {
@@ -803,6 +1185,7 @@ fn player_line_info_from_scene_data(scene_data: &SceneData) -> Option<PlayerLine
 }
 
 fn process_notify_social_data(
+    state: &mut ParserState,
     payload: &[u8],
 ) -> Result<Option<CombatEvent>, Box<dyn std::error::Error>> {
     let notify = NotifySocialData::decode(Bytes::copy_from_slice(payload))
@@ -819,7 +1202,7 @@ fn process_notify_social_data(
     };
 
     let packed = pack_scene(update.line_id, update.level_map_id);
-    if LAST_SOCIAL_SCENE.swap(packed, Ordering::Relaxed) == packed {
+    if std::mem::replace(&mut state.last_social_scene, packed) == packed {
         return Ok(None);
     }
 
@@ -955,3 +1338,109 @@ fn decode_protobuf_string(data: &[u8]) -> Result<String, Box<dyn std::error::Err
 
     Ok(name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SyncContainerDirtyData body as it arrives after zstd/framing, laid out
+    /// by hand from the wire format rather than with the dirty.rs test encoder
+    #[rustfmt::skip]
+    const DIRTY_NOTIFY: &[u8] = &[
+        // SyncContainerDirtyData.v_data: tag 1, length 194
+        0x0A, 0xC2, 0x01,
+        // CharSerialize begin, body 178 bytes
+        0xFE, 0xFF, 0xFF, 0xFF, 0xB2, 0x00, 0x00, 0x00,
+        // 1 char_id = 20481234
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xD2, 0x84, 0x38, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // 2 char_base begin, body 42 bytes
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xFE, 0xFF, 0xFF, 0xFF, 0x2A, 0x00, 0x00, 0x00,
+        //   5 name = "Tester"
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x54, 0x65, 0x73, 0x74, 0x65, 0x72, 0x00, 0x00, 0x00, 0x00,
+        //   35 fight_point = 12345
+        0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x39, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // char_base end
+        0xFD, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        // 3 scene_data begin, body 32 bytes
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xFE, 0xFF, 0xFF, 0xFF, 0x20, 0x00, 0x00, 0x00,
+        //   15 line_id = 7
+        0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   6 level_map_id = 10
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // scene_data end
+        0xFD, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        // 61 profession_list begin, body 16 bytes
+        0x3D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xFE, 0xFF, 0xFF, 0xFF, 0x10, 0x00, 0x00, 0x00,
+        //   1 cur_profession_id = 11
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x0B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // profession_list end
+        0xFD, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        // CharSerialize end
+        0xFD, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn dirty_notify_fixture_emits_player_events() {
+        let mut state = ParserState::new();
+        let diagnostics = CaptureDiagnostics::new();
+
+        let events =
+            process_sync_container_dirty_data(&mut state, DIRTY_NOTIFY, &diagnostics).unwrap();
+
+        assert_eq!(events.len(), 4, "{:?}", events);
+        match &events[0] {
+            CombatEvent::PlayerName(update) => {
+                assert_eq!(update.player_uid, 20481234);
+                assert_eq!(update.name, "Tester");
+            }
+            other => panic!("expected player name, got {:?}", other),
+        }
+        match &events[1] {
+            CombatEvent::PlayerAbilityScore(update) => {
+                assert_eq!(update.player_uid, 20481234);
+                assert_eq!(update.ability_score, 12345);
+            }
+            other => panic!("expected ability score, got {:?}", other),
+        }
+        match &events[2] {
+            CombatEvent::PlayerClass(update) => {
+                assert_eq!(update.player_uid, 20481234);
+                assert_eq!(update.class_id, 11);
+            }
+            other => panic!("expected player class, got {:?}", other),
+        }
+        match &events[3] {
+            CombatEvent::PlayerLineInfo(update) => {
+                assert_eq!(update.line_id, 7);
+                assert_eq!(update.level_map_id, Some(10));
+            }
+            other => panic!("expected line info, got {:?}", other),
+        }
+        assert_eq!(
+            diagnostics.dirty_truncated_objects.load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[test]
+    fn truncated_dirty_notify_fixture_is_an_error() {
+        let mut state = ParserState::new();
+        let diagnostics = CaptureDiagnostics::new();
+        let mut payload = DIRTY_NOTIFY[..DIRTY_NOTIFY.len() - 40].to_vec();
+        // Keep the protobuf envelope valid so the dirty stream itself is short:
+        // length 154 is varint 0x9A 0x01
+        payload[1] = 0x9A;
+
+        assert!(process_sync_container_dirty_data(&mut state, &payload, &diagnostics).is_err());
+    }
+}
@@ -0,0 +1,531 @@
+use crate::protocol::constants::tcp;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Session file layout (zstd-compressed stream, little-endian):
+///
+/// header:  b"BPSESS" | version u16
+/// records: tag u8 followed by
+///   TAG_CONNECTION:    conn_id u32 | key_len u16 | key (utf-8)
+///   TAG_FRAME:         conn_id u32 | timestamp_us u64 | len u32 | frame bytes
+///   TAG_SERVER_CHANGE: conn_id u32 | timestamp_us u64 | len u16 | endpoint (utf-8)
+///
+/// Connection keys are written once and referenced by id afterwards.
+const SESSION_MAGIC: &[u8; 6] = b"BPSESS";
+const SESSION_VERSION: u16 = 1;
+const TAG_CONNECTION: u8 = 1;
+const TAG_FRAME: u8 = 2;
+const TAG_SERVER_CHANGE: u8 = 3;
+
+pub const SESSION_FILE_EXTENSION: &str = "bpsess";
+const ZSTD_LEVEL: i32 = 3;
+/// Longest connection key or endpoint a session may hold
+const MAX_STRING_LEN: usize = 256;
+/// Flush compressed blocks regularly so a crash loses at most this much data
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Directory recorded sessions are written to
+pub fn sessions_dir() -> PathBuf {
+    crate::config::get_data_dir().join("sessions")
+}
+
+/// New timestamped session file path inside `sessions_dir()`
+pub fn new_session_path() -> io::Result<PathBuf> {
+    let dir = sessions_dir();
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!(
+        "session-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        SESSION_FILE_EXTENSION
+    )))
+}
+
+pub fn is_session_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(SESSION_FILE_EXTENSION))
+}
+
+fn to_micros(timestamp: SystemTime) -> u64 {
+    timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+fn from_micros(micros: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(micros)
+}
+
+/// Writes reassembled Blue Protocol frames to a session file
+pub struct SessionRecorder {
+    path: PathBuf,
+    encoder: zstd::stream::write::AutoFinishEncoder<'static, BufWriter<File>>,
+    connections: HashMap<String, u32>,
+    last_flush: SystemTime,
+    frame_count: u64,
+}
+
+impl SessionRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = File::create(path)?;
+        let mut encoder =
+            zstd::stream::write::Encoder::new(BufWriter::new(file), ZSTD_LEVEL)?.auto_finish();
+        encoder.write_all(SESSION_MAGIC)?;
+        encoder.write_all(&SESSION_VERSION.to_le_bytes())?;
+
+        info!("Recording session to {}", path.display());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            encoder,
+            connections: HashMap::new(),
+            last_flush: SystemTime::now(),
+            frame_count: 0,
+        })
+    }
+
+    fn connection_id(&mut self, conn_key: &str) -> io::Result<u32> {
+        if let Some(id) = self.connections.get(conn_key) {
+            return Ok(*id);
+        }
+
+        let key = checked_string(conn_key)?;
+        let id = self.connections.len() as u32;
+        self.encoder.write_all(&[TAG_CONNECTION])?;
+        self.encoder.write_all(&id.to_le_bytes())?;
+        self.encoder.write_all(&(key.len() as u16).to_le_bytes())?;
+        self.encoder.write_all(key)?;
+        self.connections.insert(conn_key.to_string(), id);
+        Ok(id)
+    }
+
+    /// Record one complete frame extracted from a TCP stream
+    pub fn record_frame(
+        &mut self,
+        conn_key: &str,
+        timestamp: SystemTime,
+        frame: &[u8],
+    ) -> io::Result<()> {
+        if frame.len() > tcp::MAX_PACKET_SIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("frame too large to record ({} bytes)", frame.len()),
+            ));
+        }
+        let id = self.connection_id(conn_key)?;
+        self.encoder.write_all(&[TAG_FRAME])?;
+        self.encoder.write_all(&id.to_le_bytes())?;
+        self.encoder
+            .write_all(&to_micros(timestamp).to_le_bytes())?;
+        self.encoder
+            .write_all(&(frame.len() as u32).to_le_bytes())?;
+        self.encoder.write_all(frame)?;
+        self.frame_count += 1;
+        self.maybe_flush(timestamp)
+    }
+
+    /// Record detection of a new game server connection
+    pub fn record_server_change(
+        &mut self,
+        conn_key: &str,
+        timestamp: SystemTime,
+        server_endpoint: &str,
+    ) -> io::Result<()> {
+        let endpoint = checked_string(server_endpoint)?;
+        let id = self.connection_id(conn_key)?;
+        self.encoder.write_all(&[TAG_SERVER_CHANGE])?;
+        self.encoder.write_all(&id.to_le_bytes())?;
+        self.encoder
+            .write_all(&to_micros(timestamp).to_le_bytes())?;
+        self.encoder
+            .write_all(&(endpoint.len() as u16).to_le_bytes())?;
+        self.encoder.write_all(endpoint)?;
+        self.maybe_flush(timestamp)
+    }
+
+    fn maybe_flush(&mut self, now: SystemTime) -> io::Result<()> {
+        if now.duration_since(self.last_flush).unwrap_or_default() >= FLUSH_INTERVAL {
+            self.encoder.flush()?;
+            self.last_flush = now;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SessionRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionRecorder")
+            .field("path", &self.path)
+            .field("frame_count", &self.frame_count)
+            .finish()
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        info!(
+            "Session recording stopped: {} ({} frames)",
+            self.path.display(),
+            self.frame_count
+        );
+    }
+}
+
+/// One complete frame read back from a session file
+#[derive(Debug, Clone)]
+pub struct SessionFrame {
+    pub timestamp: SystemTime,
+    pub conn_key: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionRecord {
+    ServerChange {
+        timestamp: SystemTime,
+        conn_key: String,
+        server_endpoint: String,
+    },
+    Frame(SessionFrame),
+}
+
+impl SessionRecord {
+    pub fn timestamp(&self) -> SystemTime {
+        match self {
+            SessionRecord::ServerChange { timestamp, .. } => *timestamp,
+            SessionRecord::Frame(frame) => frame.timestamp,
+        }
+    }
+}
+
+/// Reads records back from a session file
+pub struct SessionReader {
+    decoder: zstd::stream::read::Decoder<'static, BufReader<File>>,
+    connections: HashMap<u32, String>,
+}
+
+impl SessionReader {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut decoder = zstd::stream::read::Decoder::new(File::open(path)?)?;
+
+        let mut magic = [0u8; 6];
+        decoder.read_exact(&mut magic)?;
+        if &magic != SESSION_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a session file",
+            ));
+        }
+        let version = read_u16(&mut decoder)?;
+        if version != SESSION_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported session version {}", version),
+            ));
+        }
+
+        Ok(Self {
+            decoder,
+            connections: HashMap::new(),
+        })
+    }
+
+    /// Next record, or None at end of file. A truncated trailing record
+    /// (e.g. after a crash) is treated as end of file.
+    pub fn next_record(&mut self) -> io::Result<Option<SessionRecord>> {
+        loop {
+            let mut tag = [0u8; 1];
+            match self.decoder.read_exact(&mut tag) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e),
+            }
+
+            let result = match tag[0] {
+                TAG_CONNECTION => self.read_connection().map(|_| None),
+                TAG_FRAME => self
+                    .read_frame()
+                    .map(|frame| Some(SessionRecord::Frame(frame))),
+                TAG_SERVER_CHANGE => self.read_server_change().map(Some),
+                other => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown session record tag {}", other),
+                    ));
+                }
+            };
+
+            match result {
+                Ok(Some(record)) => return Ok(Some(record)),
+                Ok(None) => continue,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    warn!("Session file ends with a truncated record");
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn read_connection(&mut self) -> io::Result<()> {
+        let id = read_u32(&mut self.decoder)?;
+        let len = read_u16(&mut self.decoder)? as usize;
+        let key = read_string(&mut self.decoder, len)?;
+        self.connections.insert(id, key);
+        Ok(())
+    }
+
+    fn conn_key(&self, id: u32) -> String {
+        self.connections.get(&id).cloned().unwrap_or_default()
+    }
+
+    fn read_frame(&mut self) -> io::Result<SessionFrame> {
+        let id = read_u32(&mut self.decoder)?;
+        let timestamp = from_micros(read_u64(&mut self.decoder)?);
+        let len = read_u32(&mut self.decoder)?;
+        // Lengths come from the file; never allocate more than one frame can be
+        if len > tcp::MAX_PACKET_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("session frame too large ({} bytes)", len),
+            ));
+        }
+        let mut data = vec![0u8; len as usize];
+        self.decoder.read_exact(&mut data)?;
+        Ok(SessionFrame {
+            timestamp,
+            conn_key: self.conn_key(id),
+            data,
+        })
+    }
+
+    fn read_server_change(&mut self) -> io::Result<SessionRecord> {
+        let id = read_u32(&mut self.decoder)?;
+        let timestamp = from_micros(read_u64(&mut self.decoder)?);
+        let len = read_u16(&mut self.decoder)? as usize;
+        let server_endpoint = read_string(&mut self.decoder, len)?;
+        Ok(SessionRecord::ServerChange {
+            timestamp,
+            conn_key: self.conn_key(id),
+            server_endpoint,
+        })
+    }
+}
+
+impl Iterator for SessionReader {
+    type Item = io::Result<SessionRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Bytes of a string about to be written, rejected up front if the reader
+/// would refuse it so a bad value never leaves a half-written record
+fn checked_string(value: &str) -> io::Result<&[u8]> {
+    if value.len() > MAX_STRING_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("session string too long ({} bytes)", value.len()),
+        ));
+    }
+    Ok(value.as_bytes())
+}
+
+fn read_string(reader: &mut impl Read, len: usize) -> io::Result<String> {
+    if len > MAX_STRING_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("session string too long ({} bytes)", len),
+        ));
+    }
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_session_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "bptimer-test-{}-{}.{}",
+            name,
+            std::process::id(),
+            SESSION_FILE_EXTENSION
+        ))
+    }
+
+    /// Session file holding `records` (uncompressed) after the header
+    fn write_raw_session(path: &Path, records: &[u8]) {
+        let mut raw = SESSION_MAGIC.to_vec();
+        raw.extend_from_slice(&SESSION_VERSION.to_le_bytes());
+        raw.extend_from_slice(records);
+        fs::write(path, zstd::encode_all(raw.as_slice(), ZSTD_LEVEL).unwrap()).unwrap();
+    }
+
+    fn read_all(path: &Path) -> io::Result<Vec<SessionRecord>> {
+        SessionReader::open(path)?.collect()
+    }
+
+    #[test]
+    fn recorded_session_reads_back() {
+        let path = temp_session_path("roundtrip");
+        let start = from_micros(1_700_000_000_000_000);
+        let later = start + Duration::from_millis(250);
+        {
+            let mut recorder = SessionRecorder::create(&path).unwrap();
+            recorder
+                .record_server_change("a>b", start, "1.2.3.4:5003 -> 10.0.0.1:50000")
+                .unwrap();
+            recorder.record_frame("a>b", start, &[1, 2, 3]).unwrap();
+            recorder.record_frame("c>d", later, &[4; 64]).unwrap();
+        }
+
+        let records = read_all(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(records.len(), 3);
+        match &records[0] {
+            SessionRecord::ServerChange {
+                timestamp,
+                conn_key,
+                server_endpoint,
+            } => {
+                assert_eq!(*timestamp, start);
+                assert_eq!(conn_key, "a>b");
+                assert_eq!(server_endpoint, "1.2.3.4:5003 -> 10.0.0.1:50000");
+            }
+            other => panic!("expected server change, got {:?}", other),
+        }
+        let SessionRecord::Frame(second) = &records[2] else {
+            panic!("expected frame");
+        };
+        assert_eq!(second.conn_key, "c>d");
+        assert_eq!(second.timestamp, later);
+        assert_eq!(second.data, vec![4; 64]);
+        assert!(matches!(&records[1], SessionRecord::Frame(f) if f.data == [1, 2, 3]));
+    }
+
+    fn frame_record(id: u32, data: &[u8]) -> Vec<u8> {
+        let mut record = vec![TAG_FRAME];
+        record.extend_from_slice(&id.to_le_bytes());
+        record.extend_from_slice(&0u64.to_le_bytes());
+        record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        record.extend_from_slice(data);
+        record
+    }
+
+    #[test]
+    fn truncated_tail_ends_the_session() {
+        let path = temp_session_path("truncated");
+        let mut records = vec![TAG_CONNECTION];
+        records.extend_from_slice(&0u32.to_le_bytes());
+        records.extend_from_slice(&3u16.to_le_bytes());
+        records.extend_from_slice(b"a>b");
+        records.extend(frame_record(0, &[7; 16]));
+        let partial = frame_record(0, &[8; 16]);
+        records.extend_from_slice(&partial[..partial.len() - 5]);
+        write_raw_session(&path, &records);
+
+        let records = read_all(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(records.len(), 1);
+        assert!(matches!(&records[0], SessionRecord::Frame(f) if f.data == [7; 16]));
+    }
+
+    #[test]
+    fn oversized_lengths_are_rejected() {
+        let path = temp_session_path("oversized");
+        let mut records = vec![TAG_FRAME];
+        records.extend_from_slice(&0u32.to_le_bytes());
+        records.extend_from_slice(&0u64.to_le_bytes());
+        records.extend_from_slice(&u32::MAX.to_le_bytes());
+        write_raw_session(&path, &records);
+        let frame_err = read_all(&path).unwrap_err();
+
+        let mut records = vec![TAG_CONNECTION];
+        records.extend_from_slice(&0u32.to_le_bytes());
+        records.extend_from_slice(&u16::MAX.to_le_bytes());
+        write_raw_session(&path, &records);
+        let string_err = read_all(&path).unwrap_err();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(frame_err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(string_err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn long_strings_are_rejected_when_writing() {
+        let path = temp_session_path("long-string");
+        let long = "x".repeat(MAX_STRING_LEN + 1);
+        {
+            let mut recorder = SessionRecorder::create(&path).unwrap();
+            let key_err = recorder.record_frame(&long, UNIX_EPOCH, &[1]).unwrap_err();
+            let endpoint_err = recorder
+                .record_server_change("a>b", UNIX_EPOCH, &long)
+                .unwrap_err();
+            assert_eq!(key_err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(endpoint_err.kind(), io::ErrorKind::InvalidInput);
+            recorder.record_frame("a>b", UNIX_EPOCH, &[2]).unwrap();
+        }
+
+        let records = read_all(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        // Rejected records leave nothing behind
+        assert_eq!(records.len(), 1);
+        assert!(matches!(&records[0], SessionRecord::Frame(f) if f.data == [2]));
+    }
+
+    #[test]
+    fn stream_cut_mid_block_keeps_flushed_frames() {
+        let path = temp_session_path("crash");
+        let start = SystemTime::now();
+        let mut recorder = SessionRecorder::create(&path).unwrap();
+        recorder.record_frame("a>b", start, &[1; 32]).unwrap();
+        // Past FLUSH_INTERVAL, so each of these ends a flushed block
+        recorder
+            .record_frame("a>b", start + FLUSH_INTERVAL * 2, &[2; 32])
+            .unwrap();
+        let flushed_len = fs::metadata(&path).unwrap().len() as usize;
+        recorder
+            .record_frame("a>b", start + FLUSH_INTERVAL * 4, &[3; 32])
+            .unwrap();
+        // A crash never finishes the zstd frame, and may cut the last block
+        std::mem::forget(recorder);
+        let mut data = fs::read(&path).unwrap();
+        data.truncate(flushed_len + (data.len() - flushed_len) / 2);
+        fs::write(&path, &data).unwrap();
+
+        let records = read_all(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(records.len(), 2);
+        assert!(matches!(&records[1], SessionRecord::Frame(f) if f.data == [2; 32]));
+    }
+}
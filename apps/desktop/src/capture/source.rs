@@ -0,0 +1,532 @@
+use log::{error, info, warn};
+use pcap::{Active, Capture, Device, Offline, PacketHeader};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::capture::link::LinkType;
+use crate::capture::packet::clean_device_name;
+use crate::capture::session::{SessionReader, SessionRecord};
+
+const PCAP_SNAPLEN: i32 = 65535;
+const PCAP_TIMEOUT_MS: i32 = 10;
+const PCAP_FILTER: &str = "tcp";
+
+/// One step of input from a packet source
+#[derive(Debug)]
+pub enum SourceEvent<'a> {
+    /// Raw captured link-layer frame, still needs TCP reassembly
+    Packet {
+        data: &'a [u8],
+        timestamp: SystemTime,
+    },
+    /// Already reassembled game frame or server change from a recorded session
+    Session(SessionRecord),
+    /// Nothing available right now (e.g. live capture read timeout)
+    Idle,
+    /// Source is exhausted
+    Finished,
+}
+
+impl SourceEvent<'_> {
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        match self {
+            SourceEvent::Packet { timestamp, .. } => Some(*timestamp),
+            SourceEvent::Session(record) => Some(record.timestamp()),
+            SourceEvent::Idle | SourceEvent::Finished => None,
+        }
+    }
+}
+
+/// Anything that can feed the capture pipeline
+pub trait PacketSource: Send {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>>;
+
+    /// Link-layer framing of `SourceEvent::Packet` data
+    fn link_type(&self) -> LinkType {
+        LinkType::Ethernet
+    }
+
+    /// Kernel/driver packet counters, if the source has any
+    fn stats(&mut self) -> Option<pcap::Stat> {
+        None
+    }
+
+    /// Live sources support device switching, session recording and HP reporting
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    /// Switch to another capture device. Returns false if the source has no devices.
+    fn switch_device(&mut self, _idx: usize) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(false)
+    }
+}
+
+impl<S: PacketSource + ?Sized> PacketSource for Box<S> {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>> {
+        (**self).next_event()
+    }
+
+    fn link_type(&self) -> LinkType {
+        (**self).link_type()
+    }
+
+    fn stats(&mut self) -> Option<pcap::Stat> {
+        (**self).stats()
+    }
+
+    fn is_live(&self) -> bool {
+        (**self).is_live()
+    }
+
+    fn switch_device(&mut self, idx: usize) -> Result<bool, Box<dyn std::error::Error>> {
+        (**self).switch_device(idx)
+    }
+}
+
+/// Convert a pcap packet header timestamp to SystemTime
+fn packet_timestamp(header: &PacketHeader) -> SystemTime {
+    let secs = header.ts.tv_sec.max(0) as u64;
+    let micros = header.ts.tv_usec.max(0) as u64;
+    UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_micros(micros)
+}
+
+/// Resolve the datalink of an opened capture, falling back to Ethernet
+fn capture_link_type<T: pcap::Activated + ?Sized>(cap: &Capture<T>) -> LinkType {
+    let linktype = cap.get_datalink();
+    LinkType::from_pcap(linktype).unwrap_or_else(|| {
+        warn!(
+            "Unsupported datalink {} ({}), assuming Ethernet",
+            linktype.0,
+            linktype.get_name().unwrap_or_default()
+        );
+        LinkType::Ethernet
+    })
+}
+
+/// Live capture from a network device
+pub struct LivePcapSource {
+    devices: Vec<Device>,
+    cap: Capture<Active>,
+    link_type: LinkType,
+}
+
+impl LivePcapSource {
+    pub fn open(
+        devices: Vec<Device>,
+        device_index: usize,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let cap = Self::open_device(&devices, device_index)?;
+        let link_type = capture_link_type(&cap);
+        Ok(Self {
+            devices,
+            cap,
+            link_type,
+        })
+    }
+
+    fn open_device(
+        devices: &[Device],
+        device_index: usize,
+    ) -> Result<Capture<Active>, Box<dyn std::error::Error>> {
+        let device = devices.get(device_index).ok_or("Device not found")?.clone();
+        let clean_name = clean_device_name(&device);
+        info!("Starting capture on device: {}", clean_name);
+
+        let mut cap = Capture::from_device(device)?
+            .promisc(true)
+            .snaplen(PCAP_SNAPLEN)
+            .timeout(PCAP_TIMEOUT_MS)
+            .open()?;
+
+        cap.filter(PCAP_FILTER, true)?;
+        info!("Packet capture started (read-only mode)");
+        info!("Filter: {}", PCAP_FILTER);
+        info!("Waiting for Blue Protocol traffic...\n");
+
+        Ok(cap)
+    }
+}
+
+impl PacketSource for LivePcapSource {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>> {
+        match self.cap.next_packet() {
+            Ok(packet) => Ok(SourceEvent::Packet {
+                data: packet.data,
+                timestamp: packet_timestamp(packet.header),
+            }),
+            Err(pcap::Error::TimeoutExpired) => Ok(SourceEvent::Idle),
+            Err(e) => {
+                error!("Error capturing packet: {}", e);
+                Ok(SourceEvent::Idle)
+            }
+        }
+    }
+
+    fn link_type(&self) -> LinkType {
+        self.link_type
+    }
+
+    fn stats(&mut self) -> Option<pcap::Stat> {
+        self.cap.stats().ok()
+    }
+
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn switch_device(&mut self, idx: usize) -> Result<bool, Box<dyn std::error::Error>> {
+        self.cap = Self::open_device(&self.devices, idx)?;
+        self.link_type = capture_link_type(&self.cap);
+        Ok(true)
+    }
+}
+
+/// Saved .pcap/.pcapng capture file
+pub struct PcapFileSource {
+    cap: Capture<Offline>,
+    link_type: LinkType,
+}
+
+impl PcapFileSource {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut cap = Capture::from_file(path)?;
+        cap.filter(PCAP_FILTER, true)?;
+        let link_type = capture_link_type(&cap);
+        Ok(Self { cap, link_type })
+    }
+}
+
+impl PacketSource for PcapFileSource {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>> {
+        match self.cap.next_packet() {
+            Ok(packet) => Ok(SourceEvent::Packet {
+                data: packet.data,
+                timestamp: packet_timestamp(packet.header),
+            }),
+            Err(pcap::Error::NoMorePackets) => Ok(SourceEvent::Finished),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn link_type(&self) -> LinkType {
+        self.link_type
+    }
+}
+
+/// Recorded .bpsess session (frames skip TCP reassembly)
+pub struct SessionSource {
+    reader: SessionReader,
+}
+
+impl SessionSource {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            reader: SessionReader::open(path)?,
+        })
+    }
+}
+
+impl PacketSource for SessionSource {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>> {
+        Ok(match self.reader.next_record()? {
+            Some(record) => SourceEvent::Session(record),
+            None => SourceEvent::Finished,
+        })
+    }
+}
+
+/// In-memory list of raw frames, mainly for tests and tools
+#[derive(Debug)]
+pub struct MemorySource {
+    packets: VecDeque<(SystemTime, Vec<u8>)>,
+    current: Vec<u8>,
+    link_type: LinkType,
+}
+
+impl MemorySource {
+    pub fn new(link_type: LinkType, packets: Vec<(SystemTime, Vec<u8>)>) -> Self {
+        Self {
+            packets: packets.into(),
+            current: Vec::new(),
+            link_type,
+        }
+    }
+
+    pub fn push(&mut self, timestamp: SystemTime, data: Vec<u8>) {
+        self.packets.push_back((timestamp, data));
+    }
+}
+
+impl PacketSource for MemorySource {
+    fn next_event(&mut self) -> Result<SourceEvent<'_>, Box<dyn std::error::Error>> {
+        match self.packets.pop_front() {
+            Some((timestamp, data)) => {
+                self.current = data;
+                Ok(SourceEvent::Packet {
+                    data: &self.current,
+                    timestamp,
+                })
+            }
+            None => Ok(SourceEvent::Finished),
+        }
+    }
+
+    fn link_type(&self) -> LinkType {
+        self.link_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::packet::PacketCapture;
+    use crate::models::events::CombatEvent;
+    use crate::protocol::constants::{
+        AttrType, MessageMethod, SERVICE_UUID, entity, server_detection,
+    };
+    use crate::protocol::pb::{Attr, AttrCollection, EEntityType, Entity, SyncNearEntities};
+    use etherparse::PacketBuilder;
+    use prost::Message;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    const SERVER_PORT: u16 = 5003;
+    const CLIENT_PORT: u16 = 50000;
+    const PLAYER_UID: i64 = 42;
+    const PLAYER_NAME: &str = "Tester";
+
+    /// Login reply the TCP processor detects the game server by
+    fn login_payload() -> Vec<u8> {
+        let mut payload = server_detection::LOGIN_RETURN_SIGNATURE.to_vec();
+        payload.resize(server_detection::LOGIN_RETURN_SIGNATURE_SIZE, 0);
+        payload
+    }
+
+    /// Uncompressed notify frame carrying one named player appearing nearby
+    fn player_name_frame() -> Vec<u8> {
+        let mut name = vec![PLAYER_NAME.len() as u8];
+        name.extend_from_slice(PLAYER_NAME.as_bytes());
+        let body = SyncNearEntities {
+            appear: vec![Entity {
+                uuid: (PLAYER_UID << 16) | entity::TYPE_PLAYER as i64,
+                ent_type: EEntityType::EntChar as i32,
+                attrs: Some(AttrCollection {
+                    uuid: 0,
+                    attrs: vec![Attr {
+                        id: AttrType::AttrName as i32,
+                        raw_data: name,
+                    }],
+                }),
+                body_part_infos: None,
+            }],
+            disappear: Vec::new(),
+        }
+        .encode_to_vec();
+        let mut frame = ((22 + body.len()) as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(&2u16.to_be_bytes());
+        frame.extend_from_slice(&SERVICE_UUID.to_be_bytes());
+        frame.extend_from_slice(&0u32.to_be_bytes());
+        frame.extend_from_slice(&(MessageMethod::SyncNearEntities as u32).to_be_bytes());
+        frame.extend_from_slice(&body);
+        frame
+    }
+
+    /// Ethernet II frame from the server to the client
+    fn ethernet_packet(server: IpAddr, client: IpAddr, seq: u32, payload: &[u8]) -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2([1; 6], [2; 6]);
+        let builder = match (server, client) {
+            (IpAddr::V4(s), IpAddr::V4(c)) => builder.ipv4(s.octets(), c.octets(), 64),
+            (IpAddr::V6(s), IpAddr::V6(c)) => builder.ipv6(s.octets(), c.octets(), 64),
+            _ => panic!("mixed address families"),
+        };
+        let mut data = Vec::new();
+        builder
+            .tcp(SERVER_PORT, CLIENT_PORT, seq, 1024)
+            .write(&mut data, payload)
+            .unwrap();
+        data
+    }
+
+    /// Bare IP/TCP packet from the server to the client
+    fn ip_packet(server: IpAddr, client: IpAddr, seq: u32, payload: &[u8]) -> Vec<u8> {
+        let builder = match (server, client) {
+            (IpAddr::V4(s), IpAddr::V4(c)) => PacketBuilder::ipv4(s.octets(), c.octets(), 64),
+            (IpAddr::V6(s), IpAddr::V6(c)) => PacketBuilder::ipv6(s.octets(), c.octets(), 64),
+            _ => panic!("mixed address families"),
+        };
+        let mut data = Vec::new();
+        builder
+            .tcp(SERVER_PORT, CLIENT_PORT, seq, 1024)
+            .write(&mut data, payload)
+            .unwrap();
+        data
+    }
+
+    /// `ip_packet` behind a link-layer header
+    fn with_header(header: &[u8], ip: Vec<u8>) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.extend_from_slice(&ip);
+        data
+    }
+
+    /// Login reply then a player name frame, framed by `frame`
+    fn game_session(frame: impl Fn(u32, &[u8]) -> Vec<u8>) -> Vec<(SystemTime, Vec<u8>)> {
+        let login = login_payload();
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        vec![
+            (start, frame(1000, &login)),
+            (
+                start + Duration::from_millis(50),
+                frame(1000 + login.len() as u32, &player_name_frame()),
+            ),
+        ]
+    }
+
+    /// Every event the pipeline emits for `packets`
+    fn run(link_type: LinkType, packets: Vec<(SystemTime, Vec<u8>)>) -> Vec<CombatEvent> {
+        let capture =
+            PacketCapture::from_source(MemorySource::new(link_type, packets), false, None);
+        capture.events().collect()
+    }
+
+    fn assert_server_then_player(events: &[CombatEvent], endpoint: &str) {
+        assert_eq!(events.len(), 2, "{:?}", events);
+        match &events[0] {
+            CombatEvent::ServerChange(update) => assert_eq!(update.server_endpoint, endpoint),
+            other => panic!("expected server change, got {:?}", other),
+        }
+        match &events[1] {
+            CombatEvent::PlayerName(update) => {
+                assert_eq!(update.player_uid, PLAYER_UID);
+                assert_eq!(update.name, PLAYER_NAME);
+            }
+            other => panic!("expected player name, got {:?}", other),
+        }
+    }
+
+    fn v4_endpoints() -> (IpAddr, IpAddr) {
+        (
+            IpAddr::V4(Ipv4Addr::new(10, 20, 30, 40)),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)),
+        )
+    }
+
+    #[test]
+    fn ethernet_ipv4_session_emits_events() {
+        let (server, client) = v4_endpoints();
+        let events = run(
+            LinkType::Ethernet,
+            game_session(|seq, payload| ethernet_packet(server, client, seq, payload)),
+        );
+        assert_server_then_player(&events, "10.20.30.40:5003 -> 192.168.1.10:50000");
+    }
+
+    #[test]
+    fn ethernet_ipv6_session_emits_events() {
+        let server = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        let client = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2));
+        let events = run(
+            LinkType::Ethernet,
+            game_session(|seq, payload| ethernet_packet(server, client, seq, payload)),
+        );
+        assert_server_then_player(&events, "[2001:db8::1]:5003 -> [2001:db8::2]:50000");
+    }
+
+    #[test]
+    fn linux_sll_session_emits_events() {
+        let (server, client) = v4_endpoints();
+        let mut header = [0u8; 16];
+        header[14..].copy_from_slice(&0x0800u16.to_be_bytes());
+        let events = run(
+            LinkType::LinuxSll,
+            game_session(|seq, payload| {
+                with_header(&header, ip_packet(server, client, seq, payload))
+            }),
+        );
+        assert_server_then_player(&events, "10.20.30.40:5003 -> 192.168.1.10:50000");
+    }
+
+    #[test]
+    fn linux_sll2_session_emits_events() {
+        let server = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        let client = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2));
+        let mut header = [0u8; 20];
+        header[..2].copy_from_slice(&0x86DDu16.to_be_bytes());
+        let events = run(
+            LinkType::LinuxSll2,
+            game_session(|seq, payload| {
+                with_header(&header, ip_packet(server, client, seq, payload))
+            }),
+        );
+        assert_server_then_player(&events, "[2001:db8::1]:5003 -> [2001:db8::2]:50000");
+    }
+
+    #[test]
+    fn cooked_non_ip_frames_are_ignored() {
+        let (server, client) = v4_endpoints();
+        let mut header = [0u8; 16];
+        // ARP
+        header[14..].copy_from_slice(&0x0806u16.to_be_bytes());
+        let packets = game_session(|seq, payload| {
+            with_header(&header, ip_packet(server, client, seq, payload))
+        });
+        assert!(run(LinkType::LinuxSll, packets).is_empty());
+    }
+
+    #[test]
+    fn null_loopback_session_emits_events() {
+        let (server, client) = v4_endpoints();
+        // AF_INET in host byte order
+        let header = 2u32.to_le_bytes();
+        let events = run(
+            LinkType::Null,
+            game_session(|seq, payload| {
+                with_header(&header, ip_packet(server, client, seq, payload))
+            }),
+        );
+        assert_server_then_player(&events, "10.20.30.40:5003 -> 192.168.1.10:50000");
+    }
+
+    #[test]
+    fn raw_ip_session_emits_events() {
+        let (server, client) = v4_endpoints();
+        let events = run(
+            LinkType::RawIp,
+            game_session(|seq, payload| ip_packet(server, client, seq, payload)),
+        );
+        assert_server_then_player(&events, "10.20.30.40:5003 -> 192.168.1.10:50000");
+    }
+
+    #[test]
+    fn segments_split_mid_frame_are_reassembled() {
+        let (server, client) = v4_endpoints();
+        let mut packets =
+            game_session(|seq, payload| ethernet_packet(server, client, seq, payload));
+        let (timestamp, _) = packets.pop().unwrap();
+        let frame = player_name_frame();
+        let seq = 1000 + login_payload().len() as u32;
+        // Second half first, then the first half
+        packets.push((
+            timestamp,
+            ethernet_packet(server, client, seq + 10, &frame[10..]),
+        ));
+        packets.push((
+            timestamp,
+            ethernet_packet(server, client, seq, &frame[..10]),
+        ));
+
+        let events = run(LinkType::Ethernet, packets);
+        assert_server_then_player(&events, "10.20.30.40:5003 -> 192.168.1.10:50000");
+    }
+
+    #[test]
+    fn non_game_traffic_is_ignored() {
+        let (server, client) = v4_endpoints();
+        let packets = vec![(
+            UNIX_EPOCH,
+            ethernet_packet(server, client, 1, b"GET / HTTP/1.1\r\n\r\n"),
+        )];
+        assert!(run(LinkType::Ethernet, packets).is_empty());
+    }
+}
@@ -1,21 +1,36 @@
+use crate::capture::diagnostics::CaptureDiagnostics;
+use crate::capture::link::LinkType;
+use crate::capture::parser::{self, ParserState};
+use crate::capture::session::{self, SessionRecorder};
 use crate::models::events::{CombatEvent, ServerChangeUpdate};
 use crate::protocol::constants::tcp;
-use log::info;
+use log::{info, warn};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::mpsc;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, mpsc};
 use std::time::SystemTime;
 
 /// Server endpoint
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ServerEndpoint {
-    pub src_addr: String,
+    pub src_addr: IpAddr,
     pub src_port: u16,
-    pub dst_addr: String,
+    pub dst_addr: IpAddr,
     pub dst_port: u16,
 }
 
+/// `addr:port`, with IPv6 addresses in brackets
+fn format_socket(addr: &IpAddr, port: u16) -> String {
+    match addr {
+        IpAddr::V4(v4) => format!("{}:{}", v4, port),
+        IpAddr::V6(v6) => format!("[{}]:{}", v6, port),
+    }
+}
+
 impl ServerEndpoint {
-    pub fn new(src_addr: String, src_port: u16, dst_addr: String, dst_port: u16) -> Self {
+    pub fn new(src_addr: IpAddr, src_port: u16, dst_addr: IpAddr, dst_port: u16) -> Self {
         Self {
             src_addr,
             src_port,
@@ -23,11 +38,15 @@ impl ServerEndpoint {
             dst_port,
         }
     }
+}
 
-    pub fn to_string(&self) -> String {
-        format!(
-            "{}:{} -> {}:{}",
-            self.src_addr, self.src_port, self.dst_addr, self.dst_port
+impl fmt::Display for ServerEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}",
+            format_socket(&self.src_addr, self.src_port),
+            format_socket(&self.dst_addr, self.dst_port)
         )
     }
 }
@@ -35,8 +54,9 @@ impl ServerEndpoint {
 /// Directional key for a TCP stream (order-dependent)
 fn conn_key(ep: &ServerEndpoint) -> String {
     format!(
-        "{}:{}>{}:{}",
-        ep.src_addr, ep.src_port, ep.dst_addr, ep.dst_port
+        "{}>{}",
+        format_socket(&ep.src_addr, ep.src_port),
+        format_socket(&ep.dst_addr, ep.dst_port)
     )
 }
 
@@ -44,7 +64,9 @@ fn conn_key(ep: &ServerEndpoint) -> String {
 #[derive(Debug)]
 struct ConnectionState {
     next_seq: Option<u32>,
+    /// Out-of-order segments keyed by sequence number, always ahead of `next_seq`
     tcp_cache: HashMap<u32, Vec<u8>>,
+    cached_bytes: usize,
     stream_buffer: VecDeque<u8>,
     last_any_packet_time: Option<SystemTime>,
     waiting_gap_since: Option<SystemTime>,
@@ -55,6 +77,7 @@ impl ConnectionState {
         Self {
             next_seq: None,
             tcp_cache: HashMap::new(),
+            cached_bytes: 0,
             stream_buffer: VecDeque::new(),
             last_any_packet_time: None,
             waiting_gap_since: None,
@@ -65,63 +88,138 @@ impl ConnectionState {
         a.wrapping_sub(b) as i32
     }
 
-    fn force_resync_to(&mut self, seq: u32) {
-        self.tcp_cache.clear();
+    /// Skip the missing bytes and continue from the earliest cached segment
+    fn resync_to_earliest_cached(&mut self) {
+        let Some(next_seq) = self.next_seq else {
+            return;
+        };
+        let earliest = self
+            .tcp_cache
+            .keys()
+            .copied()
+            .min_by_key(|&seq| seq.wrapping_sub(next_seq));
+
         self.stream_buffer.clear();
-        self.next_seq = Some(seq);
         self.waiting_gap_since = None;
+        if let Some(seq) = earliest {
+            self.next_seq = Some(seq);
+        }
     }
 
-    fn process_segment(&mut self, seq: u32, payload: &[u8]) -> Vec<Vec<u8>> {
+    fn cache_segment(&mut self, seq: u32, payload: &[u8]) {
+        // Keep the longer copy when a retransmit starts at the same sequence
+        if let Some(existing) = self.tcp_cache.get(&seq) {
+            if existing.len() >= payload.len() {
+                return;
+            }
+            self.cached_bytes -= existing.len();
+        }
+        self.cached_bytes += payload.len();
+        self.tcp_cache.insert(seq, payload.to_vec());
+    }
+
+    /// Re-key cached segments that now straddle `next_seq` and drop fully
+    /// consumed ones
+    fn trim_cache(&mut self) {
+        let Some(next_seq) = self.next_seq else {
+            return;
+        };
+        let stale: Vec<u32> = self
+            .tcp_cache
+            .keys()
+            .copied()
+            .filter(|&seq| Self::seq_cmp(seq, next_seq) < 0)
+            .collect();
+
+        for seq in stale {
+            let Some(segment) = self.tcp_cache.remove(&seq) else {
+                continue;
+            };
+            self.cached_bytes -= segment.len();
+            let end = seq.wrapping_add(segment.len() as u32);
+            if Self::seq_cmp(end, next_seq) > 0 {
+                let skip = next_seq.wrapping_sub(seq) as usize;
+                self.cache_segment(next_seq, &segment[skip..]);
+            }
+        }
+    }
+
+    /// Move contiguous cached segments into the stream buffer
+    fn drain_cache(&mut self, diagnostics: &CaptureDiagnostics) {
+        while let Some(next_seq) = self.next_seq {
+            let Some(segment) = self.tcp_cache.remove(&next_seq) else {
+                break;
+            };
+            self.cached_bytes -= segment.len();
+            diagnostics
+                .bytes_reassembled
+                .fetch_add(segment.len() as u64, Ordering::Relaxed);
+            self.stream_buffer.extend(&segment);
+            self.next_seq = Some(next_seq.wrapping_add(segment.len() as u32));
+            self.trim_cache();
+        }
+    }
+
+    fn process_segment(
+        &mut self,
+        seq: u32,
+        payload: &[u8],
+        now: SystemTime,
+        diagnostics: &CaptureDiagnostics,
+    ) -> Vec<Vec<u8>> {
         let mut complete_packets = Vec::new();
-        let now = SystemTime::now();
 
         self.last_any_packet_time = Some(now);
 
-        if self.next_seq.is_none() {
-            if payload.len() > 4 {
-                let packet_size =
-                    u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
-                if packet_size < tcp::MAX_PACKET_SIZE as usize {
-                    self.next_seq = Some(seq);
-                }
+        if self.next_seq.is_none() && payload.len() > 4 {
+            let packet_size =
+                u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
+            if packet_size < tcp::MAX_PACKET_SIZE as usize {
+                self.next_seq = Some(seq);
             }
         }
 
-        if let Some(next_seq) = self.next_seq {
-            let cmp = Self::seq_cmp(seq, next_seq);
-            if cmp > 0 {
-                if self.waiting_gap_since.is_none() {
-                    self.waiting_gap_since = Some(now);
-                } else if let Some(gap_since) = self.waiting_gap_since {
-                    if now.duration_since(gap_since).unwrap_or_default() > tcp::GAP_TIMEOUT {
-                        self.force_resync_to(seq);
-                    }
+        match self.next_seq {
+            Some(next_seq) => {
+                let end = seq.wrapping_add(payload.len() as u32);
+                if Self::seq_cmp(end, next_seq) <= 0 {
+                    // Pure retransmit of data already consumed
+                } else if Self::seq_cmp(seq, next_seq) < 0 {
+                    // Straddles next_seq: keep only the new bytes
+                    let skip = next_seq.wrapping_sub(seq) as usize;
+                    self.cache_segment(next_seq, &payload[skip..]);
+                } else {
+                    self.cache_segment(seq, payload);
                 }
-            } else if cmp == 0 {
-                self.waiting_gap_since = None;
             }
+            // Not aligned to a frame yet; wait for a segment that is
+            None => return complete_packets,
         }
 
-        if self.next_seq.is_none() || Self::seq_cmp(seq, self.next_seq.unwrap()) >= 0 {
-            self.tcp_cache.insert(seq, payload.to_vec());
-        }
+        self.drain_cache(diagnostics);
 
-        while let Some(next_seq) = self.next_seq {
-            if let Some(segment) = self.tcp_cache.remove(&next_seq) {
-                self.stream_buffer.extend(&segment);
-                self.next_seq = Some(next_seq.wrapping_add(segment.len() as u32));
-            } else {
-                break;
+        if self.tcp_cache.is_empty() {
+            self.waiting_gap_since = None;
+        } else {
+            let gap_since = *self.waiting_gap_since.get_or_insert(now);
+            let gap_expired = now.duration_since(gap_since).unwrap_or_default() > tcp::GAP_TIMEOUT;
+            if gap_expired || self.cached_bytes > tcp::MAX_CONNECTION_CACHE_BYTES {
+                self.resync_to_earliest_cached();
+                diagnostics.gap_resyncs.fetch_add(1, Ordering::Relaxed);
+                self.drain_cache(diagnostics);
             }
         }
 
-        while self.try_extract_packet(&mut complete_packets) {}
+        while self.try_extract_packet(&mut complete_packets, diagnostics) {}
 
         complete_packets
     }
 
-    fn try_extract_packet(&mut self, complete_packets: &mut Vec<Vec<u8>>) -> bool {
+    fn try_extract_packet(
+        &mut self,
+        complete_packets: &mut Vec<Vec<u8>>,
+        diagnostics: &CaptureDiagnostics,
+    ) -> bool {
         if self.stream_buffer.len() < 4 {
             return false;
         }
@@ -135,6 +233,7 @@ impl ConnectionState {
 
         if packet_size <= 4 || packet_size > tcp::MAX_PACKET_SIZE as usize {
             self.stream_buffer.pop_front();
+            diagnostics.bytes_skipped.fetch_add(1, Ordering::Relaxed);
             return true;
         }
 
@@ -148,60 +247,210 @@ impl ConnectionState {
     }
 }
 
+/// Destination of complete game frames: session recorder, then parser
+#[derive(Debug)]
+struct FrameSink {
+    tx: mpsc::Sender<CombatEvent>,
+    parser_state: ParserState,
+    recorder: Option<SessionRecorder>,
+    diagnostics: Arc<CaptureDiagnostics>,
+}
+
+impl FrameSink {
+    fn record_server_change(&mut self, key: &str, timestamp: SystemTime, endpoint: &str) {
+        if let Some(rec) = self.recorder.as_mut()
+            && let Err(e) = rec.record_server_change(key, timestamp, endpoint)
+        {
+            warn!("Session recording failed, stopping: {}", e);
+            self.recorder = None;
+        }
+    }
+
+    fn handle_frame(&mut self, key: &str, timestamp: SystemTime, frame: &[u8]) {
+        if let Some(rec) = self.recorder.as_mut()
+            && let Err(e) = rec.record_frame(key, timestamp, frame)
+        {
+            warn!("Session recording failed, stopping: {}", e);
+            self.recorder = None;
+        }
+        self.parse(frame, timestamp);
+    }
+
+    fn parse(&mut self, frame: &[u8], timestamp: SystemTime) {
+        self.parser_state.frame_time = timestamp;
+        parser::process_bp_packet(frame, &mut self.parser_state, &self.tx, &self.diagnostics);
+    }
+}
+
 /// TCP Stream Processor — tracks multiple game connections
 #[derive(Debug)]
 pub struct TcpStreamProcessor {
     pub current_server: Option<ServerEndpoint>,
     connections: HashMap<String, ConnectionState>,
-    game_server_prefixes: HashSet<String>,
-    pub tx: mpsc::Sender<CombatEvent>,
+    /// Network addresses of detected game server subnets (host bits zeroed)
+    game_server_prefixes: HashSet<IpAddr>,
+    sink: FrameSink,
 }
 
 impl TcpStreamProcessor {
-    pub fn new(tx: mpsc::Sender<CombatEvent>) -> Self {
+    pub fn new(tx: mpsc::Sender<CombatEvent>, diagnostics: Arc<CaptureDiagnostics>) -> Self {
         Self {
             current_server: None,
             connections: HashMap::new(),
             game_server_prefixes: HashSet::new(),
-            tx,
+            sink: FrameSink {
+                tx,
+                parser_state: ParserState::new(),
+                recorder: None,
+                diagnostics,
+            },
         }
     }
 
-    fn extract_ip_prefix(addr: &str) -> Option<String> {
-        let parts: Vec<&str> = addr.split('.').collect();
-        if parts.len() >= tcp::GAME_SUBNET_PREFIX_OCTETS {
-            Some(format!("{}.{}.", parts[0], parts[1]))
-        } else {
-            None
+    /// Start or stop recording extracted frames to a new session file
+    pub fn set_recording(&mut self, enabled: bool) {
+        if enabled == self.sink.recorder.is_some() {
+            return;
+        }
+        if !enabled {
+            self.sink.recorder = None;
+            return;
+        }
+
+        match session::new_session_path().and_then(|path| SessionRecorder::create(&path)) {
+            Ok(recorder) => self.sink.recorder = Some(recorder),
+            Err(e) => warn!("Failed to start session recording: {}", e),
         }
     }
 
-    fn is_game_subnet(&self, addr: &str) -> bool {
+    pub fn is_recording(&self) -> bool {
+        self.sink.recorder.is_some()
+    }
+
+    /// Feed an already reassembled game frame (e.g. from a recorded session)
+    /// straight to the parser
+    pub fn process_frame(&mut self, frame: &[u8], timestamp: SystemTime) {
+        self.sink
+            .diagnostics
+            .frames_extracted
+            .fetch_add(1, Ordering::Relaxed);
+        self.sink.parse(frame, timestamp);
+    }
+
+    /// Announce a server change (e.g. from a recorded session)
+    pub fn set_server_endpoint(&self, server_endpoint: String) {
+        let _ = self
+            .sink
+            .tx
+            .send(CombatEvent::ServerChange(ServerChangeUpdate {
+                server_endpoint,
+            }));
+    }
+
+    /// Mask an address down to its game subnet (/16 for IPv4, /48 for IPv6)
+    fn extract_ip_prefix(addr: &IpAddr) -> IpAddr {
+        match addr {
+            IpAddr::V4(v4) => {
+                let mask = u32::MAX << (32 - tcp::GAME_SUBNET_PREFIX_LEN_V4);
+                IpAddr::V4(Ipv4Addr::from(u32::from(*v4) & mask))
+            }
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX << (128 - tcp::GAME_SUBNET_PREFIX_LEN_V6);
+                IpAddr::V6(Ipv6Addr::from(u128::from(*v6) & mask))
+            }
+        }
+    }
+
+    fn format_prefix(prefix: &IpAddr) -> String {
+        match prefix {
+            IpAddr::V4(_) => format!("{}/{}", prefix, tcp::GAME_SUBNET_PREFIX_LEN_V4),
+            IpAddr::V6(_) => format!("{}/{}", prefix, tcp::GAME_SUBNET_PREFIX_LEN_V6),
+        }
+    }
+
+    fn is_game_subnet(&self, addr: &IpAddr) -> bool {
         self.game_server_prefixes
+            .contains(&Self::extract_ip_prefix(addr))
+    }
+
+    /// Drop a tracked connection, forgetting the current server if it was one
+    /// of its directions
+    fn remove_connection(&mut self, key: &str, reason: &str) {
+        if self.connections.remove(key).is_none() {
+            return;
+        }
+        info!("Removing {} connection: {}", reason, key);
+
+        if let Some(ref server) = self.current_server {
+            let server_key = conn_key(server);
+            let server_rev_key = conn_key(&ServerEndpoint::new(
+                server.dst_addr,
+                server.dst_port,
+                server.src_addr,
+                server.src_port,
+            ));
+            if key == server_key || key == server_rev_key {
+                self.current_server = None;
+            }
+        }
+    }
+
+    fn expire_idle_connections(&mut self, now: SystemTime) {
+        let idle: Vec<String> = self
+            .connections
             .iter()
-            .any(|p| addr.starts_with(p))
+            .filter(|(_, conn)| {
+                conn.last_any_packet_time.is_some_and(|last_any| {
+                    now.duration_since(last_any).unwrap_or_default() > tcp::IDLE_TIMEOUT
+                })
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in idle {
+            self.remove_connection(&key, "idle");
+        }
     }
 
     fn process_conn_segments(
         conn: &mut ConnectionState,
+        sink: &mut FrameSink,
+        key: &str,
         seq: u32,
         payload: &[u8],
-        tx: &mpsc::Sender<CombatEvent>,
+        timestamp: SystemTime,
     ) {
-        let segments = conn.process_segment(seq, payload);
+        let segments = conn.process_segment(seq, payload, timestamp, &sink.diagnostics);
+        sink.diagnostics
+            .frames_extracted
+            .fetch_add(segments.len() as u64, Ordering::Relaxed);
         for segment in segments {
-            crate::capture::parser::process_bp_packet(&segment, tx);
+            sink.handle_frame(key, timestamp, &segment);
         }
     }
 
-    pub fn process_packet(&mut self, packet_data: &[u8]) {
-        let packet = match etherparse::SlicedPacket::from_ethernet(packet_data) {
-            Ok(p) => p,
-            Err(_) => return,
+    /// Process one captured link-layer frame. `timestamp` is the capture time of
+    /// the packet (live or from a capture file) and drives gap/idle timeouts.
+    pub fn process_packet(
+        &mut self,
+        link_type: LinkType,
+        packet_data: &[u8],
+        timestamp: SystemTime,
+    ) {
+        let packet = match link_type.slice(packet_data) {
+            Some(p) => p,
+            None => return,
         };
 
-        let ip_header = match packet.net {
-            Some(etherparse::InternetSlice::Ipv4(h)) => h,
+        let (src_addr, dst_addr) = match &packet.net {
+            Some(etherparse::InternetSlice::Ipv4(h)) => (
+                IpAddr::V4(h.header().source_addr()),
+                IpAddr::V4(h.header().destination_addr()),
+            ),
+            Some(etherparse::InternetSlice::Ipv6(h)) => (
+                IpAddr::V6(h.header().source_addr()),
+                IpAddr::V6(h.header().destination_addr()),
+            ),
             _ => return,
         };
 
@@ -210,78 +459,84 @@ impl TcpStreamProcessor {
             _ => return,
         };
 
-        let src_addr = ip_header.header().source_addr().to_string();
         let src_port = tcp_header.to_header().source_port;
-        let dst_addr = ip_header.header().destination_addr().to_string();
         let dst_port = tcp_header.to_header().destination_port;
         let payload = tcp_header.payload();
 
-        if payload.is_empty() {
+        let endpoint = ServerEndpoint::new(src_addr, src_port, dst_addr, dst_port);
+        let key = conn_key(&endpoint);
+
+        self.expire_idle_connections(timestamp);
+
+        // RST aborts both directions; FIN closes this direction once its data is in
+        if tcp_header.rst() {
+            let reverse_key =
+                conn_key(&ServerEndpoint::new(dst_addr, dst_port, src_addr, src_port));
+            self.remove_connection(&key, "reset");
+            self.remove_connection(&reverse_key, "reset");
             return;
         }
+        let is_fin = tcp_header.fin();
 
-        let endpoint = ServerEndpoint::new(src_addr.clone(), src_port, dst_addr.clone(), dst_port);
-        let key = conn_key(&endpoint);
+        if payload.is_empty() {
+            if is_fin {
+                self.remove_connection(&key, "closed");
+            }
+            return;
+        }
 
         // Already-tracked connection
-        if self.connections.contains_key(&key) {
-            let now = SystemTime::now();
-            let conn = self.connections.get_mut(&key).unwrap();
-
-            if let Some(last_any) = conn.last_any_packet_time {
-                if now.duration_since(last_any).unwrap_or_default() > tcp::IDLE_TIMEOUT {
-                    info!("Removing idle connection: {}", endpoint.to_string());
-                    self.connections.remove(&key);
-                    if let Some(ref server) = self.current_server {
-                        let server_key = conn_key(server);
-                        let server_rev_key = conn_key(&ServerEndpoint::new(
-                            server.dst_addr.clone(),
-                            server.dst_port,
-                            server.src_addr.clone(),
-                            server.src_port,
-                        ));
-                        if key == server_key || key == server_rev_key {
-                            self.current_server = None;
-                        }
-                    }
-                    return;
-                }
+        if let Some(conn) = self.connections.get_mut(&key) {
+            Self::process_conn_segments(
+                conn,
+                &mut self.sink,
+                &key,
+                tcp_header.sequence_number(),
+                payload,
+                timestamp,
+            );
+            if is_fin {
+                self.remove_connection(&key, "closed");
             }
-
-            Self::process_conn_segments(conn, tcp_header.sequence_number(), payload, &self.tx);
             return;
         }
 
         // New connection — check for game server signature
         let payload_len = payload.len();
-        let is_likely_bp = payload_len >= tcp::BP_DETECT_MIN_PAYLOAD
-            && payload_len < tcp::BP_DETECT_MAX_PAYLOAD
+        let is_likely_bp = (tcp::BP_DETECT_MIN_PAYLOAD..tcp::BP_DETECT_MAX_PAYLOAD)
+            .contains(&payload_len)
             && payload[4] == 0
             && !((src_port == tcp::TLS_PORT || dst_port == tcp::TLS_PORT)
                 && payload_len >= tcp::TLS_LARGE_PACKET_THRESHOLD);
 
         if is_likely_bp && crate::capture::parser::detect_server_in_packet(payload, &endpoint) {
-            if let Some(prefix) = Self::extract_ip_prefix(&src_addr) {
-                if self.game_server_prefixes.insert(prefix.clone()) {
-                    info!("Game server subnet detected: {}*", prefix);
-                }
+            let prefix = Self::extract_ip_prefix(&src_addr);
+            if self.game_server_prefixes.insert(prefix) {
+                info!(
+                    "Game server subnet detected: {}",
+                    Self::format_prefix(&prefix)
+                );
             }
 
             self.current_server = Some(endpoint.clone());
 
-            info!(
-                "Blue Protocol server detected! Server: {}",
-                endpoint.to_string()
-            );
+            info!("Blue Protocol server detected! Server: {}", endpoint);
 
-            let _ = self.tx.send(CombatEvent::ServerChange(ServerChangeUpdate {
-                server_endpoint: endpoint.to_string(),
-            }));
+            self.set_server_endpoint(endpoint.to_string());
+            self.sink
+                .record_server_change(&key, timestamp, &endpoint.to_string());
 
             let mut conn = ConnectionState::new();
-            conn.last_any_packet_time = Some(SystemTime::now());
-
-            Self::process_conn_segments(&mut conn, tcp_header.sequence_number(), payload, &self.tx);
+            conn.last_any_packet_time = Some(timestamp);
+
+            Self::process_conn_segments(
+                &mut conn,
+                &mut self.sink,
+                &key,
+                tcp_header.sequence_number(),
+                payload,
+                timestamp,
+            );
 
             self.connections.insert(key, conn);
             return;
@@ -296,17 +551,234 @@ impl TcpStreamProcessor {
                 return;
             }
 
-            info!(
-                "Auto-tracking game subnet connection: {}",
-                endpoint.to_string()
-            );
+            info!("Auto-tracking game subnet connection: {}", endpoint);
 
             let mut conn = ConnectionState::new();
-            conn.last_any_packet_time = Some(SystemTime::now());
-
-            Self::process_conn_segments(&mut conn, tcp_header.sequence_number(), payload, &self.tx);
+            conn.last_any_packet_time = Some(timestamp);
+
+            Self::process_conn_segments(
+                &mut conn,
+                &mut self.sink,
+                &key,
+                tcp_header.sequence_number(),
+                payload,
+                timestamp,
+            );
 
             self.connections.insert(key, conn);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Game frame of `len` bytes: big-endian size prefix, then `fill`
+    fn frame(len: usize, fill: u8) -> Vec<u8> {
+        let mut data = (len as u32).to_be_bytes().to_vec();
+        data.resize(len, fill);
+        data
+    }
+
+    fn feed(conn: &mut ConnectionState, seq: u32, payload: &[u8]) -> Vec<Vec<u8>> {
+        conn.process_segment(
+            seq,
+            payload,
+            SystemTime::UNIX_EPOCH,
+            &CaptureDiagnostics::new(),
+        )
+    }
+
+    #[test]
+    fn retransmit_straddling_next_seq_keeps_only_new_bytes() {
+        let a = frame(10, 0xaa);
+        let b = frame(10, 0xbb);
+        let stream = [a.clone(), b.clone()].concat();
+        let mut conn = ConnectionState::new();
+
+        assert!(feed(&mut conn, 1000, &stream[..6]).is_empty());
+        assert_eq!(conn.next_seq, Some(1006));
+
+        // Retransmit of the first 6 bytes plus 8 new ones
+        assert_eq!(feed(&mut conn, 1000, &stream[..14]), vec![a]);
+        assert_eq!(conn.next_seq, Some(1014));
+
+        // Pure retransmit is ignored
+        assert!(feed(&mut conn, 1000, &stream[..14]).is_empty());
+        assert_eq!(feed(&mut conn, 1014, &stream[14..]), vec![b]);
+        assert!(conn.tcp_cache.is_empty());
+        assert_eq!(conn.cached_bytes, 0);
+    }
+
+    #[test]
+    fn out_of_order_segments_drain_in_order() {
+        let a = frame(10, 1);
+        let b = frame(12, 2);
+        let c = frame(8, 3);
+        let mut conn = ConnectionState::new();
+
+        assert_eq!(feed(&mut conn, 1000, &a), vec![a]);
+        assert!(feed(&mut conn, 1022, &c).is_empty());
+        assert_eq!(conn.cached_bytes, c.len());
+
+        assert_eq!(feed(&mut conn, 1010, &b), vec![b, c]);
+        assert_eq!(conn.next_seq, Some(1030));
+        assert!(conn.tcp_cache.is_empty());
+        assert_eq!(conn.cached_bytes, 0);
+    }
+
+    #[test]
+    fn overlapping_cached_segment_is_trimmed_after_drain() {
+        let a = frame(10, 1);
+        let b = frame(10, 2);
+        let c = frame(10, 3);
+        let stream = [a.clone(), b.clone(), c.clone()].concat();
+        let mut conn = ConnectionState::new();
+
+        assert_eq!(feed(&mut conn, 1000, &a), vec![a]);
+        // Starts inside b and runs to the end of c
+        assert!(feed(&mut conn, 1015, &stream[15..]).is_empty());
+        assert_eq!(feed(&mut conn, 1010, &stream[10..20]), vec![b, c]);
+        assert!(conn.tcp_cache.is_empty());
+        assert_eq!(conn.cached_bytes, 0);
+    }
+
+    #[test]
+    fn cache_cap_forces_resync_past_gap() {
+        let diagnostics = CaptureDiagnostics::new();
+        let now = SystemTime::UNIX_EPOCH;
+        let a = frame(10, 1);
+        let mut conn = ConnectionState::new();
+        assert_eq!(conn.process_segment(1000, &a, now, &diagnostics), vec![a]);
+
+        // Bytes 1010..2000 never arrive
+        let big = frame(tcp::MAX_PACKET_SIZE as usize / 2, 7);
+        let mut seq = 2000u32;
+        let mut extracted = Vec::new();
+        while diagnostics.gap_resyncs.load(Ordering::Relaxed) == 0 {
+            assert!(conn.cached_bytes <= tcp::MAX_CONNECTION_CACHE_BYTES);
+            extracted = conn.process_segment(seq, &big, now, &diagnostics);
+            seq = seq.wrapping_add(big.len() as u32);
+        }
+
+        assert!(extracted.len() > 1);
+        assert!(extracted.iter().all(|packet| *packet == big));
+        assert_eq!(conn.next_seq, Some(seq));
+        assert!(conn.tcp_cache.is_empty());
+        assert_eq!(conn.cached_bytes, 0);
+    }
+
+    #[test]
+    fn gap_timeout_forces_resync() {
+        let diagnostics = CaptureDiagnostics::new();
+        let start = SystemTime::UNIX_EPOCH;
+        let a = frame(10, 1);
+        let c = frame(10, 3);
+        let mut conn = ConnectionState::new();
+
+        assert_eq!(conn.process_segment(1000, &a, start, &diagnostics), vec![a]);
+        assert!(
+            conn.process_segment(1020, &c, start, &diagnostics)
+                .is_empty()
+        );
+
+        let later = start + tcp::GAP_TIMEOUT * 2;
+        let d = frame(10, 4);
+        assert_eq!(
+            conn.process_segment(1030, &d, later, &diagnostics),
+            vec![c, d]
+        );
+        assert_eq!(diagnostics.gap_resyncs.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn sequence_numbers_wrap_past_u32_max() {
+        let a = frame(10, 1);
+        let b = frame(10, 2);
+        let c = frame(10, 3);
+        let start = u32::MAX - 4;
+        let mut conn = ConnectionState::new();
+
+        assert_eq!(feed(&mut conn, start, &a), vec![a]);
+        assert_eq!(conn.next_seq, Some(5));
+
+        // c after the wrap arrives first, then a retransmit straddling it
+        assert!(feed(&mut conn, 15, &c).is_empty());
+        let ab = [frame(10, 1), b.clone()].concat();
+        assert_eq!(feed(&mut conn, start, &ab), vec![b, c]);
+        assert_eq!(conn.next_seq, Some(25));
+        assert!(conn.tcp_cache.is_empty());
+    }
+
+    fn tracked_processor(client: ServerEndpoint) -> TcpStreamProcessor {
+        let (tx, _rx) = mpsc::channel();
+        let mut processor = TcpStreamProcessor::new(tx, Arc::new(CaptureDiagnostics::new()));
+        let server = ServerEndpoint::new(
+            client.dst_addr,
+            client.dst_port,
+            client.src_addr,
+            client.src_port,
+        );
+        processor
+            .connections
+            .insert(conn_key(&client), ConnectionState::new());
+        processor
+            .connections
+            .insert(conn_key(&server), ConnectionState::new());
+        processor.current_server = Some(server);
+        processor
+    }
+
+    fn tcp_packet(
+        from: &ServerEndpoint,
+        flags: impl FnOnce(
+            etherparse::PacketBuilderStep<etherparse::TcpHeader>,
+        ) -> etherparse::PacketBuilderStep<etherparse::TcpHeader>,
+    ) -> Vec<u8> {
+        let (IpAddr::V4(src), IpAddr::V4(dst)) = (from.src_addr, from.dst_addr) else {
+            panic!("IPv4 endpoints only");
+        };
+        let builder = etherparse::PacketBuilder::ethernet2([1; 6], [2; 6])
+            .ipv4(src.octets(), dst.octets(), 64)
+            .tcp(from.src_port, from.dst_port, 1000, 1024);
+        let mut data = Vec::new();
+        flags(builder).write(&mut data, &[]).unwrap();
+        data
+    }
+
+    fn client_endpoint() -> ServerEndpoint {
+        ServerEndpoint::new(
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)),
+            50000,
+            IpAddr::V4(Ipv4Addr::new(10, 20, 30, 40)),
+            5003,
+        )
+    }
+
+    #[test]
+    fn rst_drops_both_directions() {
+        let client = client_endpoint();
+        let mut processor = tracked_processor(client.clone());
+
+        let packet = tcp_packet(&client, |b| b.rst());
+        processor.process_packet(LinkType::Ethernet, &packet, SystemTime::UNIX_EPOCH);
+
+        assert!(processor.connections.is_empty());
+        assert!(processor.current_server.is_none());
+    }
+
+    #[test]
+    fn fin_drops_only_its_direction() {
+        let client = client_endpoint();
+        let mut processor = tracked_processor(client.clone());
+
+        let packet = tcp_packet(&client, |b| b.fin());
+        processor.process_packet(LinkType::Ethernet, &packet, SystemTime::UNIX_EPOCH);
+
+        assert!(!processor.connections.contains_key(&conn_key(&client)));
+        assert_eq!(processor.connections.len(), 1);
+        // The client half was one direction of the current server
+        assert!(processor.current_server.is_none());
+    }
+}
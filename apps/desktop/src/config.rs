@@ -4,22 +4,28 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-fn get_settings_path() -> PathBuf {
+/// Per-user app data directory (holds settings.json and recorded data).
+/// Falls back to the current directory if it cannot be created.
+pub fn get_data_dir() -> PathBuf {
     if let Some(app_data_dir) = dirs::data_local_dir() {
-        let settings_dir = app_data_dir.join("BPTimer");
-        if let Err(e) = fs::create_dir_all(&settings_dir) {
+        let data_dir = app_data_dir.join("BPTimer");
+        if let Err(e) = fs::create_dir_all(&data_dir) {
             warn!(
                 "Failed to create settings directory: {}. Using current directory.",
                 e
             );
-            return PathBuf::from("settings.json");
+            return PathBuf::from(".");
         }
-        settings_dir.join("settings.json")
+        data_dir
     } else {
-        PathBuf::from("settings.json")
+        PathBuf::from(".")
     }
 }
 
+fn get_settings_path() -> PathBuf {
+    get_data_dir().join("settings.json")
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Settings {
     pub window_opacity: f32,
@@ -51,9 +57,17 @@ pub struct Settings {
     pub clear_combat_data_on_server_change: bool, // Clear data when server/channel changes
     #[serde(default)]
     pub show_ability_score_in_name: bool, // Show ability score appended to player name
+    #[serde(default = "default_true")]
+    pub show_threat_panel: bool, // Show entities with the local player on their hate list
+    #[serde(default = "default_true")]
+    pub show_body_parts: bool, // Show part HP and breaks of bosses with breakable parts
+    #[serde(default)]
+    pub show_cooldown_strip: bool, // Show the local player's skill cooldowns above the table
     // DPS calculation settings
     #[serde(default = "default_dps_cutoff_seconds")]
     pub dps_calculation_cutoff_seconds: f32, // Seconds after last hit to stop DPS calculation
+    #[serde(default = "default_encounter_idle_seconds")]
+    pub encounter_idle_seconds: f32, // Seconds without hits that end an encounter
     // Text color (RGBA)
     #[serde(default = "default_text_color")]
     pub text_color: [u8; 4], // RGBA color for UI text
@@ -71,6 +85,9 @@ pub struct Settings {
     // Last detected region
     #[serde(default)]
     pub last_effective_region: Option<String>,
+    // Record reassembled game frames to session files
+    #[serde(default)]
+    pub record_sessions: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -117,6 +134,7 @@ impl HotkeySettings {
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
+#[allow(clippy::upper_case_acronyms)]
 pub enum MobTimersRegion {
     DEV,
     CN,
@@ -246,13 +264,18 @@ impl Default for Settings {
             clear_combat_data_idle_seconds: None,
             clear_combat_data_on_server_change: true,
             show_ability_score_in_name: false,
+            show_threat_panel: true,
+            show_body_parts: true,
+            show_cooldown_strip: false,
             text_color: [255, 255, 255, 255], // White text by default (RGBA)
             show_console: false,              // Console hidden by default
             hotkeys: default_hotkeys(),
             dps_calculation_cutoff_seconds: default_dps_cutoff_seconds(),
+            encounter_idle_seconds: default_encounter_idle_seconds(),
             sort_column: Some(2),
             sort_descending: true,
             last_effective_region: None,
+            record_sessions: false,
         }
     }
 }
@@ -266,9 +289,15 @@ impl Settings {
         let settings_path = get_settings_path();
         if settings_path.exists() {
             match fs::read_to_string(&settings_path) {
-                Ok(content) => match serde_json::from_str(&content) {
-                    Ok(settings) => {
+                Ok(content) => match serde_json::from_str::<Settings>(&content) {
+                    Ok(mut settings) => {
                         info!("Loaded settings from {:?}", settings_path);
+                        // Column was renamed to say whose shields it counts
+                        if settings.hidden_columns.remove("Absorbed") {
+                            settings
+                                .hidden_columns
+                                .insert("Absorbed (taken)".to_string());
+                        }
                         return settings;
                     }
                     Err(e) => {
@@ -310,6 +339,9 @@ fn default_hidden_columns() -> std::collections::HashSet<String> {
     hidden.insert("Lucky%".to_string());
     hidden.insert("Heal".to_string());
     hidden.insert("Taken".to_string());
+    hidden.insert("Eff. DMG".to_string());
+    hidden.insert("Overkill".to_string());
+    hidden.insert("Absorbed (taken)".to_string());
     hidden
 }
 
@@ -317,6 +349,10 @@ fn default_dps_cutoff_seconds() -> f32 {
     10.0
 }
 
+fn default_encounter_idle_seconds() -> f32 {
+    15.0
+}
+
 fn default_sort_column() -> Option<usize> {
     Some(2)
 }
@@ -21,6 +21,12 @@ pub struct HotkeyManager {
     registered_hotkeys: HashMap<HotkeyAction, HotKey>,
 }
 
+impl Default for HotkeyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl HotkeyManager {
     pub fn new() -> Self {
         let manager = match GlobalHotKeyManager::new() {
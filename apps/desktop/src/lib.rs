@@ -0,0 +1,13 @@
+// Include build-time generated config
+include!(concat!(env!("OUT_DIR"), "/config.rs"));
+
+pub mod api;
+pub mod capture;
+pub mod config;
+pub mod hotkeys;
+pub mod models;
+pub mod protocol;
+pub mod stats;
+pub mod ui;
+pub mod updater;
+pub mod utils;
@@ -1,7 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use eframe::egui;
-use log::{info, warn};
+use log::{error, info, warn};
 
 // Load .env file if it exists (non-fatal if missing)
 fn load_env() {
@@ -18,21 +18,7 @@ fn load_env() {
     }
 }
 
-// Include build-time generated config
-include!(concat!(env!("OUT_DIR"), "/config.rs"));
-
-mod api;
-mod capture;
-mod config;
-mod hotkeys;
-mod models;
-mod protocol;
-mod stats;
-mod ui;
-mod updater;
-mod utils;
-
-use ui::app::DpsMeterApp;
+use bptimer_desktop::ui::app::DpsMeterApp;
 
 fn main() -> eframe::Result {
     // Load .env file before initializing logger
@@ -41,7 +27,7 @@ fn main() -> eframe::Result {
     // Initialize console based on settings (if settings file exists)
     #[cfg(windows)]
     {
-        let settings = crate::config::Settings::load();
+        let settings = bptimer_desktop::config::Settings::load();
         if settings.show_console {
             use windows_sys::Win32::System::Console::AllocConsole;
             unsafe {
@@ -59,11 +45,20 @@ fn main() -> eframe::Result {
         self_update::cargo_crate_version!()
     );
 
-    let mut hotkey_manager = crate::hotkeys::HotkeyManager::new();
+    let replay = match bptimer_desktop::capture::packet::ReplayOptions::from_args(std::env::args())
+    {
+        Ok(replay) => replay,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let mut hotkey_manager = bptimer_desktop::hotkeys::HotkeyManager::new();
 
     // Load settings and register initial hotkeys
-    let mut settings = crate::config::Settings::load();
-    hotkey_manager.reload_from_settings(&mut settings);
+    let settings = bptimer_desktop::config::Settings::load();
+    hotkey_manager.reload_from_settings(&settings);
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -82,7 +77,7 @@ fn main() -> eframe::Result {
         options,
         Box::new(move |cc| {
             egui_material_icons::initialize(&cc.egui_ctx);
-            Ok(Box::new(DpsMeterApp::new(cc, hotkey_manager)))
+            Ok(Box::new(DpsMeterApp::new(cc, hotkey_manager, replay)))
         }),
     )
 }
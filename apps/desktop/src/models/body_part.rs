@@ -0,0 +1,141 @@
+use crate::models::events::{BodyPartHit, BodyPartUpdate};
+use crate::protocol::pb::BodyPartState;
+use chrono::{DateTime, Local, TimeZone};
+use std::collections::{BTreeMap, HashMap};
+
+/// One breakable part of a monster
+#[derive(Debug, Clone, Default)]
+pub struct BodyPart {
+    pub hp: u64,
+    pub max_hp: u64,
+    /// Raw BodyPartState value
+    pub state: i32,
+    /// Capture time of every break, oldest first (parts can regenerate)
+    pub breaks: Vec<DateTime<Local>>,
+    /// Part damage per player uid
+    pub damage_by_player: HashMap<i64, i64>,
+}
+
+impl BodyPart {
+    pub fn is_broken(&self) -> bool {
+        self.state == BodyPartState::BodyPartStateDead as i32 || (self.max_hp > 0 && self.hp == 0)
+    }
+
+    pub fn is_injured(&self) -> bool {
+        self.state == BodyPartState::BodyPartStateInjury as i32
+    }
+
+    pub fn hp_fraction(&self) -> f32 {
+        if self.max_hp == 0 {
+            0.0
+        } else {
+            (self.hp as f64 / self.max_hp as f64) as f32
+        }
+    }
+
+    /// Players by part damage, most first
+    pub fn top_players(&self, limit: usize) -> Vec<(i64, i64)> {
+        sorted_damage(self.damage_by_player.iter(), limit)
+    }
+}
+
+/// Body parts of one entity, keyed by part id
+#[derive(Debug, Clone, Default)]
+pub struct EntityParts {
+    pub base_id: Option<u32>,
+    pub parts: BTreeMap<i32, BodyPart>,
+}
+
+impl EntityParts {
+    /// Players by damage over all parts, most first
+    pub fn top_players(&self, limit: usize) -> Vec<(i64, i64)> {
+        let mut totals: HashMap<i64, i64> = HashMap::new();
+        for part in self.parts.values() {
+            for (uid, damage) in &part.damage_by_player {
+                *totals.entry(*uid).or_insert(0) += damage;
+            }
+        }
+        sorted_damage(totals.iter(), limit)
+    }
+}
+
+fn sorted_damage<'a>(
+    damage: impl Iterator<Item = (&'a i64, &'a i64)>,
+    limit: usize,
+) -> Vec<(i64, i64)> {
+    let mut rows: Vec<(i64, i64)> = damage.map(|(uid, dmg)| (*uid, *dmg)).collect();
+    rows.sort_by_key(|b| std::cmp::Reverse(b.1));
+    rows.truncate(limit);
+    rows
+}
+
+/// Part HP, breaks and part damage of nearby entities with breakable parts.
+/// Entries are dropped when the entity leaves view.
+#[derive(Debug, Clone, Default)]
+pub struct BodyPartTracker {
+    entities: HashMap<i64, EntityParts>,
+}
+
+impl BodyPartTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_update(&mut self, update: &BodyPartUpdate) {
+        let entity = self.entities.entry(update.uuid).or_default();
+        if update.base_id.is_some() {
+            entity.base_id = update.base_id;
+        }
+        for status in &update.parts {
+            let part = entity.parts.entry(status.part_id).or_default();
+            let was_broken = part.is_broken();
+            part.hp = status.hp;
+            part.max_hp = status.max_hp;
+            part.state = status.state;
+            if part.is_broken() && !was_broken {
+                let broken_at = Local
+                    .timestamp_millis_opt(update.received_ms)
+                    .single()
+                    .unwrap_or_else(Local::now);
+                part.breaks.push(broken_at);
+            }
+        }
+    }
+
+    pub fn record_hit(&mut self, hit: &BodyPartHit) {
+        let entity = self.entities.entry(hit.target_uuid).or_default();
+        if hit.target_base_id.is_some() {
+            entity.base_id = hit.target_base_id;
+        }
+        *entity
+            .parts
+            .entry(hit.part_id)
+            .or_default()
+            .damage_by_player
+            .entry(hit.player_uid)
+            .or_insert(0) += hit.damage;
+    }
+
+    /// Entities ordered by uuid
+    pub fn entities(&self) -> Vec<(i64, &EntityParts)> {
+        let mut entities: Vec<(i64, &EntityParts)> = self
+            .entities
+            .iter()
+            .map(|(uuid, parts)| (*uuid, parts))
+            .collect();
+        entities.sort_by_key(|(uuid, _)| *uuid);
+        entities
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn remove(&mut self, uuid: i64) {
+        self.entities.remove(&uuid);
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+}
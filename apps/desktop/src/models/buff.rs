@@ -0,0 +1,115 @@
+use instant::Instant;
+
+/// One buff instance currently on a player
+#[derive(Clone, Debug)]
+pub struct ActiveBuff {
+    pub base_id: i32,
+    pub layer: i32,
+    pub duration_ms: i32,
+    pub caster_uuid: i64,
+}
+
+/// Uptime of one buff (by base id) on a player. Instances of the same buff
+/// from different casters overlap; the buff counts as up while any is active.
+#[derive(Clone, Debug, Default)]
+pub struct BuffUptime {
+    pub applications: u32,
+    pub refreshes: u32,
+    pub max_layer: i32,
+    /// Most recent caster (raw uuid), 0 if unknown
+    pub last_caster_uuid: i64,
+    /// Duration reported by the last application (ms), 0 for permanent buffs
+    pub last_duration_ms: i32,
+    active_secs: f32,
+    layer_secs: f32,
+    current_layer: i32,
+    active_instances: u32,
+    active_since: Option<Instant>,
+}
+
+impl BuffUptime {
+    /// Fold the time since the last change into the totals
+    fn accumulate(&mut self, now: Instant) {
+        if let Some(since) = self.active_since {
+            let elapsed = now.duration_since(since).as_secs_f32();
+            self.active_secs += elapsed;
+            self.layer_secs += elapsed * self.current_layer as f32;
+            self.active_since = Some(now);
+        }
+    }
+
+    pub fn start(&mut self, buff: &ActiveBuff, now: Instant) {
+        self.accumulate(now);
+        self.applications += 1;
+        self.last_caster_uuid = buff.caster_uuid;
+        self.last_duration_ms = buff.duration_ms;
+        self.active_instances += 1;
+        if self.active_since.is_none() {
+            self.active_since = Some(now);
+        }
+        self.set_layer(buff.layer, now);
+    }
+
+    pub fn refresh(&mut self, now: Instant) {
+        self.accumulate(now);
+        self.refreshes += 1;
+    }
+
+    pub fn set_layer(&mut self, layer: i32, now: Instant) {
+        self.accumulate(now);
+        self.current_layer = layer.max(1);
+        self.max_layer = self.max_layer.max(self.current_layer);
+    }
+
+    pub fn stop(&mut self, now: Instant) {
+        self.accumulate(now);
+        self.active_instances = self.active_instances.saturating_sub(1);
+        if self.active_instances == 0 {
+            self.active_since = None;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active_since.is_some()
+    }
+
+    /// Seconds the buff has been up, including a still running instance
+    pub fn active_secs(&self, now: Instant) -> f32 {
+        let running = self
+            .active_since
+            .map(|since| now.duration_since(since).as_secs_f32())
+            .unwrap_or(0.0);
+        self.active_secs + running
+    }
+
+    /// Time-weighted average stack count while the buff was up
+    pub fn average_layer(&self, now: Instant) -> f32 {
+        let active = self.active_secs(now);
+        if active <= 0.0 {
+            return self.current_layer as f32;
+        }
+        let running = self
+            .active_since
+            .map(|since| now.duration_since(since).as_secs_f32() * self.current_layer as f32)
+            .unwrap_or(0.0);
+        (self.layer_secs + running) / active
+    }
+
+    /// Average time per application
+    pub fn average_duration_secs(&self, now: Instant) -> f32 {
+        if self.applications > 0 {
+            self.active_secs(now) / self.applications as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Percentage of `encounter_secs` the buff was up
+    pub fn uptime(&self, now: Instant, encounter_secs: f32) -> f32 {
+        if encounter_secs > 0.0 {
+            (self.active_secs(now) / encounter_secs * 100.0).min(100.0)
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of recent SyncServerTime samples considered by the estimator
+const MAX_CLOCK_SAMPLES: usize = 8;
+
+/// Round trips longer than this are not trusted for the offset
+const MAX_SAMPLE_RTT_MS: i64 = 5_000;
+
+#[derive(Debug, Clone, Copy)]
+struct ClockSample {
+    offset_ms: i64,
+    rtt_ms: i64,
+}
+
+/// Estimates the offset between the local PC clock and the game server clock
+/// from SyncServerTime samples. The sample with the shortest round trip wins,
+/// since its midpoint is the least affected by network asymmetry.
+#[derive(Debug, Clone, Default)]
+pub struct ServerClock {
+    samples: VecDeque<ClockSample>,
+    offset_ms: Option<i64>,
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample: `client_ms` is the local clock when the sync was requested,
+    /// `server_ms` the server clock in the reply and `received_ms` the local clock
+    /// when the reply arrived
+    pub fn add_sample(&mut self, client_ms: i64, server_ms: i64, received_ms: i64) {
+        let rtt_ms = received_ms - client_ms;
+        let sample = if client_ms > 0 && (0..=MAX_SAMPLE_RTT_MS).contains(&rtt_ms) {
+            ClockSample {
+                offset_ms: server_ms - (client_ms + rtt_ms / 2),
+                rtt_ms,
+            }
+        } else {
+            // No usable request time; assume the reply took no time to arrive
+            ClockSample {
+                offset_ms: server_ms - received_ms,
+                rtt_ms: MAX_SAMPLE_RTT_MS,
+            }
+        };
+
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_CLOCK_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.offset_ms = self
+            .samples
+            .iter()
+            .min_by_key(|s| s.rtt_ms)
+            .map(|s| s.offset_ms);
+    }
+
+    /// Server clock minus local clock, once at least one sample arrived
+    pub fn offset_ms(&self) -> Option<i64> {
+        self.offset_ms
+    }
+
+    /// Convert a local time to server time (ms since epoch)
+    pub fn to_server_ms(&self, local: SystemTime) -> Option<i64> {
+        self.offset_ms.map(|offset| unix_millis(local) + offset)
+    }
+
+    /// Current server time (ms since epoch), if synced
+    pub fn now_ms(&self) -> Option<i64> {
+        self.to_server_ms(SystemTime::now())
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.offset_ms = None;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::protocol::pb::EEntityType;
 use instant::Instant;
 
 #[derive(Clone, Debug)]
@@ -17,3 +18,106 @@ pub struct DamageTakenEntry {
     pub timestamp: Instant,
     pub damage: i64,
 }
+
+/// Aggregated hits of a single skill
+#[derive(Clone, Debug, Default)]
+pub struct SkillStats {
+    pub total: f32,
+    pub hits: u32,
+    pub crit_hits: u32,
+    pub lucky_hits: u32,
+    /// Hits both critical and lucky, also counted in `crit_hits` and `lucky_hits`
+    pub crit_lucky_hits: u32,
+    pub max_hit: f32,
+}
+
+impl SkillStats {
+    pub fn record(&mut self, value: f32, is_crit: bool, is_lucky: bool) {
+        self.total += value;
+        self.hits += 1;
+        if is_crit {
+            self.crit_hits += 1;
+        }
+        if is_lucky {
+            self.lucky_hits += 1;
+        }
+        if is_crit && is_lucky {
+            self.crit_lucky_hits += 1;
+        }
+        if value > self.max_hit {
+            self.max_hit = value;
+        }
+    }
+
+    pub fn crit_rate(&self) -> f32 {
+        if self.hits > 0 {
+            (self.crit_hits as f32 / self.hits as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn lucky_rate(&self) -> f32 {
+        if self.hits > 0 {
+            (self.lucky_hits as f32 / self.hits as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn average_hit(&self) -> f32 {
+        if self.hits > 0 {
+            self.total / self.hits as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Percentage of `total` this skill contributed
+    pub fn share(&self, total: f32) -> f32 {
+        if total > 0.0 {
+            (self.total / total) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Damage a player dealt to a single target entity
+#[derive(Clone, Debug, Default)]
+pub struct TargetStats {
+    /// Monster base id, None for players and unidentified entities
+    pub base_id: Option<u32>,
+    pub damage: SkillStats,
+    pub effective_damage: f32,
+    pub overkill_damage: f32,
+    pub first_hit_time: Option<Instant>,
+    pub last_hit_time: Option<Instant>,
+}
+
+impl TargetStats {
+    pub fn record(&mut self, value: f32, is_crit: bool, is_lucky: bool, now: Instant) {
+        self.damage.record(value, is_crit, is_lucky);
+        if self.first_hit_time.is_none() {
+            self.first_hit_time = Some(now);
+        }
+        self.last_hit_time = Some(now);
+    }
+}
+
+/// Kind of entity a player's damage came from. Summons are re-created during
+/// a fight, so they are grouped by type and base id rather than by uuid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DamageSource {
+    /// Raw EEntityType of the attacker
+    pub entity_type: i32,
+    /// Monster base id of a summon, when known
+    pub base_id: Option<u32>,
+}
+
+impl DamageSource {
+    /// Hit dealt by the player character itself
+    pub fn is_direct(&self) -> bool {
+        self.entity_type == EEntityType::EntChar as i32
+    }
+}
@@ -0,0 +1,112 @@
+use crate::models::events::SkillCooldown;
+use instant::Instant;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Cooldown state and idle time of one local player skill
+#[derive(Debug, Clone)]
+pub struct SkillCooldownState {
+    pub skill_level_id: i32,
+    pub is_charge: bool,
+    pub duration_ms: i32,
+    pub ready_at: Instant,
+    /// Cooldowns started (casts) since the analysis was reset
+    pub casts: u32,
+    /// Seconds spent off cooldown but unused while in combat
+    pub idle_secs: f32,
+    begin_server_ms: i64,
+}
+
+impl SkillCooldownState {
+    pub fn is_ready(&self, now: Instant) -> bool {
+        self.ready_at <= now
+    }
+
+    pub fn remaining_secs(&self, now: Instant) -> f32 {
+        self.ready_at.saturating_duration_since(now).as_secs_f32()
+    }
+}
+
+/// Local player's skill cooldowns from SyncToMeDelta, plus an off-cooldown
+/// idle analysis. Idle time only accrues through `tick` while in combat.
+#[derive(Debug, Clone, Default)]
+pub struct CooldownTracker {
+    skills: HashMap<i32, SkillCooldownState>,
+    last_tick: Option<Instant>,
+}
+
+impl CooldownTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a cooldown sync. `server_now_ms` converts the server begin time
+    /// to a local deadline; without it the full duration is assumed to remain.
+    pub fn apply(&mut self, cooldown: &SkillCooldown, now: Instant, server_now_ms: Option<i64>) {
+        let remaining_ms = match server_now_ms {
+            Some(server_now) if cooldown.begin_server_ms > 0 => {
+                cooldown.begin_server_ms + cooldown.duration_ms as i64 - server_now
+            }
+            _ => cooldown.duration_ms as i64,
+        }
+        .clamp(0, cooldown.duration_ms.max(0) as i64);
+        let ready_at = now + Duration::from_millis(remaining_ms as u64);
+
+        let state = self
+            .skills
+            .entry(cooldown.skill_level_id)
+            .or_insert_with(|| SkillCooldownState {
+                skill_level_id: cooldown.skill_level_id,
+                is_charge: cooldown.is_charge,
+                duration_ms: cooldown.duration_ms,
+                ready_at,
+                casts: 0,
+                idle_secs: 0.0,
+                begin_server_ms: 0,
+            });
+        if state.begin_server_ms != cooldown.begin_server_ms {
+            state.casts += 1;
+            state.begin_server_ms = cooldown.begin_server_ms;
+        }
+        state.is_charge = cooldown.is_charge;
+        state.duration_ms = cooldown.duration_ms;
+        state.ready_at = ready_at;
+    }
+
+    /// Accrue idle time for skills that are off cooldown
+    pub fn tick(&mut self, now: Instant, in_combat: bool) {
+        if let (Some(last), true) = (self.last_tick, in_combat) {
+            for state in self.skills.values_mut() {
+                let idle_from = state.ready_at.max(last);
+                if idle_from < now {
+                    state.idle_secs += now.duration_since(idle_from).as_secs_f32();
+                }
+            }
+        }
+        self.last_tick = Some(now);
+    }
+
+    /// Skills ordered by id
+    pub fn skills(&self) -> Vec<&SkillCooldownState> {
+        let mut skills: Vec<&SkillCooldownState> = self.skills.values().collect();
+        skills.sort_by_key(|s| s.skill_level_id);
+        skills
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Clear cast counts and idle time, keeping the live cooldowns
+    pub fn reset_analysis(&mut self) {
+        for state in self.skills.values_mut() {
+            state.casts = 0;
+            state.idle_secs = 0.0;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.skills.clear();
+        self.last_tick = None;
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Local};
+use instant::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecapEventKind {
+    Hit,
+    Heal,
+}
+
+/// One incoming hit or heal kept for death recaps
+#[derive(Clone, Debug)]
+pub struct RecapEvent {
+    pub timestamp: Instant,
+    pub kind: RecapEventKind,
+    /// Raw uuid of the attacker or healer
+    pub source_uuid: i64,
+    /// Monster base id of the attacker, when known
+    pub source_base_id: Option<u32>,
+    pub skill_id: i32,
+    /// HP lost or healed
+    pub amount: i64,
+    /// Damage absorbed by shields (hits only)
+    pub absorbed: i64,
+}
+
+/// Incoming hits and heals leading up to one death
+#[derive(Clone, Debug)]
+pub struct DeathRecap {
+    pub died_at: Instant,
+    pub wall_time: DateTime<Local>,
+    /// Oldest first, ending with the killing blow
+    pub events: Vec<RecapEvent>,
+}
+
+impl DeathRecap {
+    pub fn killing_blow(&self) -> Option<&RecapEvent> {
+        self.events
+            .iter()
+            .rev()
+            .find(|e| e.kind == RecapEventKind::Hit)
+    }
+
+    pub fn total(&self, kind: RecapEventKind) -> i64 {
+        self.events
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| e.amount)
+            .sum()
+    }
+
+    /// Seconds between `event` and the death (positive = before)
+    pub fn seconds_before_death(&self, event: &RecapEvent) -> f32 {
+        self.died_at
+            .saturating_duration_since(event.timestamp)
+            .as_secs_f32()
+    }
+}
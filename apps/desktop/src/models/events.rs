@@ -1,39 +1,75 @@
+use serde::Serialize;
+
 /// Individual damage hit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DamageHit {
     pub player_uid: i64,
+    pub skill_id: i32,
     pub damage: i64,
     pub is_crit: bool,
     pub is_lucky: bool,
+    /// Raw uuid of the entity that dealt the hit (a summon or pet of
+    /// `player_uid`, or the player itself)
+    pub attacker_uuid: i64,
+    /// Raw EEntityType of the attacker
+    pub attacker_entity_type: i32,
+    /// Monster base id of a summoned attacker, when the parser has seen it
+    pub attacker_base_id: Option<u32>,
+    /// Raw EDamageSource (skill, bullet, buff DoT, fall, ...)
+    pub damage_source: i32,
+    /// Raw EDamageProperty (element)
+    pub property: i32,
+    /// Raw EDamageMode (physical/magical)
+    pub damage_mode: i32,
+    /// HP and shield the hit actually removed (`damage` minus overkill)
+    pub effective_damage: i64,
+    /// Damage beyond the target's remaining HP on a killing blow
+    pub overkill: i64,
+    /// Raw uuid of the entity that was hit
+    pub target_uuid: i64,
+    /// Monster base id of the target, when the parser has seen it
+    pub target_base_id: Option<u32>,
+    /// The hit killed the target
+    pub is_dead: bool,
 }
 
 /// Individual healing hit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HealingHit {
     pub player_uid: i64,
+    /// Player that received the heal
+    pub target_uid: i64,
+    pub skill_id: i32,
     pub healing: i64,
     pub is_crit: bool,
     pub is_lucky: bool,
 }
 
 /// Individual damage taken hit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DamageTakenHit {
     pub player_uid: i64,
     pub hp_lessen: i64,
+    /// Damage absorbed by shields instead of HP
+    pub shield_lessen: i64,
     pub is_miss: bool,
     pub is_dead: bool,
+    /// Raw uuid of the entity that dealt the hit
+    pub attacker_uuid: i64,
+    /// Monster base id of the attacker, when the parser has seen it
+    pub attacker_base_id: Option<u32>,
+    pub skill_id: i32,
 }
 
 /// Player name update event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlayerNameUpdate {
     pub player_uid: i64,
     pub name: String,
 }
 
 /// Entity position update event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EntityPositionUpdate {
     pub uuid: i64,
     pub entity_type: EntityType,
@@ -43,14 +79,39 @@ pub struct EntityPositionUpdate {
     pub max_hp: Option<u64>,      // Max HP (if available)
 }
 
+/// Why an entity left the client's area of interest (proto: EDisappearType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DisappearReason {
+    /// Despawned or walked out of range
+    LeftRange,
+    Died,
+    Destroyed,
+    /// Moved to another scene or line
+    Transferred,
+}
+
+impl DisappearReason {
+    pub fn is_death(self) -> bool {
+        self == DisappearReason::Died
+    }
+}
+
+/// Entity removed from the nearby entity list
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityDisappearUpdate {
+    pub uuid: i64,
+    pub reason: DisappearReason,
+    pub mob_base_id: Option<u32>, // Known base_id of the mob, if any
+}
+
 /// Local player position update event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct LocalPlayerPositionUpdate {
     pub position: Position,
 }
 
 /// Position data
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct Position {
     pub x: f32,
     pub y: f32,
@@ -58,54 +119,149 @@ pub struct Position {
 }
 
 /// Entity type
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum EntityType {
     Player,
     Monster,
 }
 
 /// Server change event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ServerChangeUpdate {
     pub server_endpoint: String,
 }
 
 /// Player account info update event (account_id and uid)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlayerAccountInfoUpdate {
     pub account_id: String,
     pub uid: i64,
 }
 
 /// Scene/line from CharSerialize.scene_data or SocialNtf notify (NotifySocialData → scene_data).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlayerLineInfoUpdate {
     pub line_id: u32,
     pub level_map_id: Option<u32>,
 }
 
 /// Module data update event (extracted modules from SyncContainerData)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDataUpdate {
     pub modules: Vec<crate::utils::modules::Module>,
 }
 
 /// Player class update event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlayerClassUpdate {
     pub player_uid: i64,
     pub class_id: i32,
 }
 
 /// Player ability score update event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlayerAbilityScoreUpdate {
     pub player_uid: i64,
     pub ability_score: i32,
 }
 
+/// Game server clock sample (SyncServerTime)
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerTimeUpdate {
+    pub client_ms: i64, // Client clock when the sync was requested (echoed by the server)
+    pub server_ms: i64, // Server clock when the reply was sent
+    pub received_ms: i64, // Local capture time of the reply
+}
+
+/// What happened to a buff instance
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum BuffChange {
+    /// Full buff info synced (new buff, or a re-sync of an existing one)
+    Applied {
+        base_id: i32,
+        layer: i32,
+        duration_ms: i32,
+        caster_uuid: i64,
+    },
+    Removed,
+    /// Duration refreshed or buff replaced in place
+    Refreshed,
+    LayerAdded,
+    LayerRemoved,
+}
+
+/// Buff or debuff change on a player (BuffInfoSync / BuffEffectSync)
+#[derive(Debug, Clone, Serialize)]
+pub struct BuffUpdate {
+    pub player_uid: i64,
+    pub buff_uuid: i32,
+    pub change: BuffChange,
+}
+
+/// Entity with the local player on its hate list
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HateEntry {
+    pub uuid: i64,
+    /// Monster base id, when the parser has seen it
+    pub base_id: Option<u32>,
+}
+
+/// Local player's hate list (AoiSyncToMeDelta.sync_hate_ids)
+#[derive(Debug, Clone, Serialize)]
+pub struct HateListUpdate {
+    pub entries: Vec<HateEntry>,
+}
+
+/// One local player skill cooldown (AoiSyncToMeDelta.sync_skill_c_ds)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SkillCooldown {
+    pub skill_level_id: i32,
+    /// Server time the cooldown started (ms since epoch)
+    pub begin_server_ms: i64,
+    /// Effective cooldown length (ms)
+    pub duration_ms: i32,
+    /// Charge-based skill; the cooldown is the recharge of one charge
+    pub is_charge: bool,
+}
+
+/// Local player's skill cooldown sync
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillCooldownUpdate {
+    pub cooldowns: Vec<SkillCooldown>,
+}
+
+/// Damage a player dealt to a breakable body part (SyncDamageInfo.part_infos)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BodyPartHit {
+    pub player_uid: i64,
+    pub target_uuid: i64,
+    pub target_base_id: Option<u32>,
+    pub part_id: i32,
+    pub damage: i64,
+}
+
+/// HP and break state of one body part
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BodyPartStatus {
+    pub part_id: i32,
+    pub hp: u64,
+    pub max_hp: u64,
+    /// Raw BodyPartState value
+    pub state: i32,
+}
+
+/// Body part sync of one entity (AoiSyncDelta/Entity body_part_infos)
+#[derive(Debug, Clone, Serialize)]
+pub struct BodyPartUpdate {
+    pub uuid: i64,
+    pub base_id: Option<u32>,
+    pub parts: Vec<BodyPartStatus>,
+    pub received_ms: i64, // Local capture time of the update
+}
+
 /// Combat event enum
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
 pub enum CombatEvent {
     Damage(DamageHit),
     Healing(HealingHit),
@@ -113,6 +269,7 @@ pub enum CombatEvent {
     PlayerName(PlayerNameUpdate),
     ServerChange(ServerChangeUpdate),
     EntityPosition(EntityPositionUpdate),
+    EntityDisappear(EntityDisappearUpdate),
     LocalPlayerPosition(LocalPlayerPositionUpdate),
     PlayerAccountInfo(PlayerAccountInfoUpdate),
     PlayerLineInfo(PlayerLineInfoUpdate),
@@ -120,4 +277,10 @@ pub enum CombatEvent {
     PlayerClass(PlayerClassUpdate),
     PlayerAbilityScore(PlayerAbilityScoreUpdate),
     SceneIp(String),
+    ServerTime(ServerTimeUpdate),
+    Buff(BuffUpdate),
+    HateList(HateListUpdate),
+    SkillCooldowns(SkillCooldownUpdate),
+    BodyPartHit(BodyPartHit),
+    BodyParts(BodyPartUpdate),
 }
@@ -1,8 +1,18 @@
+pub mod body_part;
+pub mod buff;
+pub mod clock;
 pub mod combat;
+pub mod cooldown;
+pub mod death;
 pub mod events;
 pub mod mob;
 pub mod player;
 pub mod radar;
+pub mod threat;
 
-pub use combat::{DamageEntry, DamageTakenEntry, HealingEntry};
-pub use player::{PlayerInfoCache, PlayerState, PlayerStats};
+pub use buff::{ActiveBuff, BuffUptime};
+pub use combat::{
+    DamageEntry, DamageSource, DamageTakenEntry, HealingEntry, SkillStats, TargetStats,
+};
+pub use death::{DeathRecap, RecapEvent, RecapEventKind};
+pub use player::{FilteredDamage, PlayerInfoCache, PlayerState, PlayerStats};
@@ -1,5 +1,12 @@
-use crate::models::combat::{DamageEntry, DamageTakenEntry, HealingEntry};
+use crate::models::buff::{ActiveBuff, BuffUptime};
+use crate::models::clock::ServerClock;
+use crate::models::combat::{
+    DamageEntry, DamageSource, DamageTakenEntry, HealingEntry, SkillStats, TargetStats,
+};
+use crate::models::cooldown::CooldownTracker;
+use crate::models::death::{DeathRecap, RecapEvent};
 use instant::Instant;
+use std::collections::{HashMap, VecDeque};
 
 #[derive(Debug, Clone, Default)]
 pub struct PlayerState {
@@ -8,6 +15,8 @@ pub struct PlayerState {
     pub line_id: Option<u32>,
     pub level_map_id: Option<u32>,
     pub scene_ip: Option<String>,
+    pub server_clock: ServerClock,
+    pub cooldowns: CooldownTracker,
 }
 
 impl PlayerState {
@@ -39,10 +48,10 @@ impl PlayerState {
         if line_id > 0 {
             self.line_id = Some(line_id);
         }
-        if let Some(mid) = level_map_id {
-            if mid > 0 {
-                self.level_map_id = Some(mid);
-            }
+        if let Some(mid) = level_map_id
+            && mid > 0
+        {
+            self.level_map_id = Some(mid);
         }
     }
 
@@ -61,6 +70,11 @@ impl PlayerState {
     pub fn get_scene_ip(&self) -> Option<String> {
         self.scene_ip.clone()
     }
+
+    /// Current game server time (ms since epoch), None until a SyncServerTime arrived
+    pub fn get_server_time_ms(&self) -> Option<i64> {
+        self.server_clock.now_ms()
+    }
 }
 
 /// Player metadata stored in cache
@@ -168,6 +182,10 @@ pub struct PlayerStats {
     pub lucky_hits: u32,
     pub crit_lucky_hits: u32,
 
+    // Raw damage minus overkill on killing blows
+    pub effective_damage: f32,
+    pub overkill_damage: f32,
+
     // DPS tracking
     pub current_dps: f32,
     pub max_dps: f32,
@@ -175,6 +193,25 @@ pub struct PlayerStats {
     pub dps_history: Vec<f32>,
     pub damage_window: Vec<DamageEntry>,
 
+    // Per-skill breakdown (skill id => aggregate)
+    pub damage_skills: HashMap<i32, SkillStats>,
+    pub healing_skills: HashMap<i32, SkillStats>,
+
+    // Per-target damage (raw target uuid => aggregate)
+    pub damage_targets: HashMap<i64, TargetStats>,
+
+    // Self vs summon/pet split of damage
+    pub damage_sources: HashMap<DamageSource, SkillStats>,
+
+    // Damage by raw EDamageProperty, EDamageMode and EDamageSource
+    pub damage_elements: HashMap<i32, SkillStats>,
+    pub damage_modes: HashMap<i32, SkillStats>,
+    pub damage_source_types: HashMap<i32, SkillStats>,
+
+    // Buffs (buff base id => uptime) and live instances (buff uuid => buff)
+    pub buffs: HashMap<i32, BuffUptime>,
+    pub active_buffs: HashMap<i32, ActiveBuff>,
+
     pub total_healing: f32,
     pub normal_healing: f32,
     pub critical_healing: f32,
@@ -192,11 +229,18 @@ pub struct PlayerStats {
     pub healing_window: Vec<HealingEntry>,
 
     pub total_damage_taken: f32,
+    /// Damage taken that shields absorbed. SyncDamageInfo does not name the
+    /// shield's caster, so this is credited to the shielded player.
+    pub total_damage_absorbed: f32,
     pub max_single_hit_taken: f32,
     pub total_hits_taken: u32,
     pub miss_count: u32,
     pub death_count: u32,
 
+    // Rolling buffer of incoming hits/heals and a recap per death
+    pub recent_incoming: VecDeque<RecapEvent>,
+    pub death_recaps: Vec<DeathRecap>,
+
     // DTPS tracking
     pub current_dtps: f32,
     pub max_dtps: f32,
@@ -227,11 +271,22 @@ impl PlayerStats {
             critical_hits: 0,
             lucky_hits: 0,
             crit_lucky_hits: 0,
+            effective_damage: 0.0,
+            overkill_damage: 0.0,
             current_dps: 0.0,
             max_dps: 0.0,
             max_single_hit: 0.0,
             dps_history: vec![0.0; 600],
             damage_window: Vec::new(),
+            damage_skills: HashMap::new(),
+            healing_skills: HashMap::new(),
+            damage_targets: HashMap::new(),
+            damage_sources: HashMap::new(),
+            damage_elements: HashMap::new(),
+            damage_modes: HashMap::new(),
+            damage_source_types: HashMap::new(),
+            buffs: HashMap::new(),
+            active_buffs: HashMap::new(),
             total_healing: 0.0,
             normal_healing: 0.0,
             critical_healing: 0.0,
@@ -246,10 +301,13 @@ impl PlayerStats {
             max_hps: 0.0,
             healing_window: Vec::new(),
             total_damage_taken: 0.0,
+            total_damage_absorbed: 0.0,
             max_single_hit_taken: 0.0,
             total_hits_taken: 0,
             miss_count: 0,
             death_count: 0,
+            recent_incoming: VecDeque::new(),
+            death_recaps: Vec::new(),
             current_dtps: 0.0,
             max_dtps: 0.0,
             damage_taken_window: Vec::new(),
@@ -264,21 +322,118 @@ impl PlayerStats {
     }
 
     pub fn get_total_dps(&self, cutoff_seconds: f32) -> f32 {
-        if let (Some(first), Some(last)) = (self.first_damage_time, self.last_damage_time) {
-            let now = Instant::now();
-            let time_since_last = now.duration_since(last).as_secs_f32();
-
-            let duration = if time_since_last > cutoff_seconds {
-                let combat_duration = last.duration_since(first).as_secs_f32();
-                combat_duration + cutoff_seconds
-            } else {
-                now.duration_since(first).as_secs_f32()
+        match (self.first_damage_time, self.last_damage_time) {
+            (Some(first), Some(last)) => average_dps(
+                self.total_damage - self.dps_session_start_damage,
+                first,
+                last,
+                cutoff_seconds,
+            ),
+            _ => 0.0,
+        }
+    }
+
+    /// Damage totals restricted to targets matching `filter`, None if the
+    /// player dealt no damage to any of them
+    pub fn filtered_by_target(
+        &self,
+        filter: impl Fn(i64, &TargetStats) -> bool,
+    ) -> Option<FilteredDamage> {
+        let mut filtered: Option<FilteredDamage> = None;
+        for (uuid, target) in &self.damage_targets {
+            if !filter(*uuid, target) {
+                continue;
+            }
+            let damage = &target.damage;
+            let totals = filtered.get_or_insert_with(FilteredDamage::default);
+            totals.total_damage += damage.total;
+            totals.total_hits += damage.hits;
+            // Same buckets as PlayerStats: crit+lucky hits count in neither
+            totals.critical_hits += damage.crit_hits - damage.crit_lucky_hits;
+            totals.lucky_hits += damage.lucky_hits - damage.crit_lucky_hits;
+            totals.crit_lucky_hits += damage.crit_lucky_hits;
+            totals.max_single_hit = totals.max_single_hit.max(damage.max_hit);
+            totals.first_damage_time = match (totals.first_damage_time, target.first_hit_time) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
             };
+            totals.last_damage_time = totals.last_damage_time.max(target.last_hit_time);
+            totals.effective_damage += target.effective_damage;
+            totals.overkill_damage += target.overkill_damage;
+        }
+        filtered
+    }
+}
+
+/// A player's damage to a subset of their targets
+#[derive(Clone, Debug, Default)]
+pub struct FilteredDamage {
+    pub total_damage: f32,
+    pub total_hits: u32,
+    pub critical_hits: u32,
+    pub lucky_hits: u32,
+    pub crit_lucky_hits: u32,
+    pub max_single_hit: f32,
+    pub effective_damage: f32,
+    pub overkill_damage: f32,
+    pub first_damage_time: Option<Instant>,
+    pub last_damage_time: Option<Instant>,
+}
 
-            let session_damage = self.total_damage - self.dps_session_start_damage;
-            let duration_whole_secs = duration.round().max(1.0);
-            return session_damage / duration_whole_secs;
+impl FilteredDamage {
+    pub fn get_total_dps(&self, cutoff_seconds: f32) -> f32 {
+        match (self.first_damage_time, self.last_damage_time) {
+            (Some(first), Some(last)) => {
+                average_dps(self.total_damage, first, last, cutoff_seconds)
+            }
+            _ => 0.0,
         }
-        0.0
+    }
+}
+
+/// Damage over the whole seconds from `first` until now, or until
+/// `cutoff_seconds` after `last` once combat has gone idle
+fn average_dps(damage: f32, first: Instant, last: Instant, cutoff_seconds: f32) -> f32 {
+    let now = Instant::now();
+    let time_since_last = now.duration_since(last).as_secs_f32();
+
+    let duration = if time_since_last > cutoff_seconds {
+        let combat_duration = last.duration_since(first).as_secs_f32();
+        combat_duration + cutoff_seconds
+    } else {
+        now.duration_since(first).as_secs_f32()
+    };
+
+    damage / duration.round().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filtered_by_target_sums_matching_targets_only() {
+        let now = Instant::now();
+        let mut player = PlayerStats::new(1);
+        let boss = player.damage_targets.entry(100).or_default();
+        boss.record(50.0, true, true, now);
+        boss.record(30.0, true, false, now);
+        boss.record(20.0, false, true, now);
+        player
+            .damage_targets
+            .entry(200)
+            .or_default()
+            .record(500.0, true, true, now);
+
+        let filtered = player.filtered_by_target(|uuid, _| uuid == 100).unwrap();
+
+        assert_eq!(filtered.total_damage, 100.0);
+        assert_eq!(filtered.total_hits, 3);
+        assert_eq!(filtered.critical_hits, 1);
+        assert_eq!(filtered.lucky_hits, 1);
+        assert_eq!(filtered.crit_lucky_hits, 1);
+        assert_eq!(filtered.max_single_hit, 50.0);
+        assert_eq!(filtered.first_damage_time, Some(now));
+        assert!(player.filtered_by_target(|uuid, _| uuid == 300).is_none());
     }
 }
@@ -103,6 +103,13 @@ impl RadarState {
         self.uuid_to_base_id.insert(uuid, base_id);
     }
 
+    /// Forget a mob entity that left the area of interest.
+    /// Returns the removed radar entry, if the mob was being tracked.
+    pub fn remove_mob_uuid(&mut self, uuid: i64, base_id: Option<u32>) -> Option<(u32, RadarMob)> {
+        let base_id = self.uuid_to_base_id.remove(&uuid).or(base_id)?;
+        self.tracked_mobs.remove(&base_id).map(|mob| (base_id, mob))
+    }
+
     pub fn clear(&mut self) {
         self.player_position = None;
         self.tracked_mobs.clear();
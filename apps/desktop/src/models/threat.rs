@@ -0,0 +1,43 @@
+use crate::models::events::HateEntry;
+use crate::utils::constants::is_tracked_mob;
+
+/// Entities that currently have the local player on their hate list.
+/// An empty `sync_hate_ids` cannot be told apart from an absent one, so entries
+/// are dropped when the entity disappears or combat goes idle instead.
+#[derive(Debug, Clone, Default)]
+pub struct ThreatState {
+    entries: Vec<HateEntry>,
+}
+
+impl ThreatState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, entries: Vec<HateEntry>) {
+        self.entries = entries;
+    }
+
+    pub fn remove(&mut self, uuid: i64) {
+        self.entries.retain(|e| e.uuid != uuid);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn entries(&self) -> &[HateEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// A tracked boss has the local player on its hate list
+    pub fn has_tracked_boss(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.base_id.is_some_and(is_tracked_mob))
+    }
+}
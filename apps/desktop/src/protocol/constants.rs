@@ -40,6 +40,7 @@ impl MessageType {
 /// RPC method ID enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u32)]
+#[allow(clippy::enum_variant_names)]
 pub enum MessageMethod {
     SyncNearEntities = 0x00000006,
     SyncContainerData = 0x00000015,
@@ -61,6 +62,17 @@ impl MessageMethod {
             _ => None,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessageMethod::SyncNearEntities => "SyncNearEntities",
+            MessageMethod::SyncContainerData => "SyncContainerData",
+            MessageMethod::SyncContainerDirtyData => "SyncContainerDirtyData",
+            MessageMethod::SyncServerTime => "SyncServerTime",
+            MessageMethod::SyncNearDeltaInfo => "SyncNearDeltaInfo",
+            MessageMethod::SyncToMeDeltaInfo => "SyncToMeDeltaInfo",
+        }
+    }
 }
 
 /// Packet type parsing constants
@@ -96,16 +108,34 @@ pub mod entity {
     /// Entity type mask (lower 16 bits of UUID)
     pub const TYPE_MASK: u16 = 0xFFFF;
 
+    /// The EEntityType value sits above the low 6 bits of the type field
+    pub const TYPE_SHIFT: u16 = 6;
+
+    /// Upper bound on remembered mob UUID -> base_id mappings per stream
+    pub const MAX_MOB_UUID_MAPPINGS: usize = 4096;
+
     #[inline]
     pub fn is_player(uuid: i64) -> bool {
         (uuid as u16 & TYPE_MASK) == TYPE_PLAYER
     }
 
+    /// Raw EEntityType value encoded in a UUID (player => EntChar)
+    #[inline]
+    pub fn get_entity_type(uuid: i64) -> i32 {
+        ((uuid as u16 & TYPE_MASK) >> TYPE_SHIFT) as i32
+    }
+
     /// Extract player UID from UUID (upper 48 bits)
     #[inline]
     pub fn get_player_uid(uuid: i64) -> i64 {
         uuid >> 16
     }
+
+    /// Inverse of `get_player_uid`
+    #[inline]
+    pub fn get_player_uuid(uid: i64) -> i64 {
+        (uid << 16) | TYPE_PLAYER as i64
+    }
 }
 
 /// Server detection signatures and constants
@@ -130,7 +160,11 @@ pub mod tcp {
     pub const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
     pub const MAX_PACKET_SIZE: u32 = 0x000FFFFF;
     pub const MAX_TRACKED_CONNECTIONS: usize = 16;
-    pub const GAME_SUBNET_PREFIX_OCTETS: usize = 2;
+    // Out-of-order bytes buffered per connection before skipping the gap
+    pub const MAX_CONNECTION_CACHE_BYTES: usize = 4 * 1024 * 1024;
+    // Prefix length shared by game servers of one region
+    pub const GAME_SUBNET_PREFIX_LEN_V4: u32 = 16;
+    pub const GAME_SUBNET_PREFIX_LEN_V6: u32 = 48;
     pub const MIN_NON_SYSTEM_PORT: u16 = 1000;
     pub const BP_DETECT_MIN_PAYLOAD: usize = 10;
     pub const BP_DETECT_MAX_PAYLOAD: usize = 2000;
@@ -142,6 +176,7 @@ pub mod tcp {
 /// Used for parsing SyncNearEntities message attributes
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u32)]
+#[allow(clippy::enum_variant_names)]
 pub enum AttrType {
     AttrName = 0x01,
     AttrId = 0x0A,
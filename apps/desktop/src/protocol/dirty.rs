@@ -0,0 +1,544 @@
+//! Decoder for SyncContainerDirtyData deltas.
+//!
+//! `SyncContainerDirtyData.v_data` is not protobuf but the server's own dirty
+//! stream, applied on top of the last full `CharSerialize`. All values are
+//! little-endian and padded to 8 bytes:
+//!
+//! object:   OBJECT_BEGIN u32 | body_len i32 | fields... | OBJECT_END u32 | pad i32
+//! field:    field_number u32 | pad i32 | value
+//! int32:    value u32 | pad i32
+//! int64:    value i64
+//! string:   len u32 | pad i32 | bytes | pad i32
+//! repeated: count u32 | pad i32 | values...   (replaces the whole list)
+//! map:      count u32 | pad i32 | (key, value object)...   (merges by key)
+//!
+//! Field numbers are the protobuf field numbers of the same message. Unknown
+//! fields are not typed on the wire: nested objects are skipped using their
+//! body length, anything else ends the enclosing object early (skipped using
+//! its body length) and is counted in `DirtySections::truncated_objects`.
+
+use crate::protocol::pb::{
+    CharBaseInfo, CharSerialize, Item, ItemPackage, Mod, ModInfo, ModNewAttr, Package,
+    ProfessionList, SceneData,
+};
+use log::debug;
+
+const OBJECT_BEGIN: u32 = 0xFFFF_FFFE;
+const OBJECT_END: u32 = 0xFFFF_FFFD;
+
+/// Upper bound on list/map lengths, guards against misaligned reads
+const MAX_COLLECTION_LEN: u32 = 0x10000;
+
+type DirtyResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Parts of `CharSerialize` touched by a dirty delta
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirtySections {
+    pub char_base: bool,
+    pub scene_data: bool,
+    pub modules: bool,
+    pub profession: bool,
+    /// Objects whose remaining fields were skipped after an unknown
+    /// non-object field
+    pub truncated_objects: u32,
+}
+
+impl DirtySections {
+    pub fn any(&self) -> bool {
+        self.char_base || self.scene_data || self.modules || self.profession
+    }
+}
+
+struct DirtyReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    truncated_objects: u32,
+}
+
+impl<'a> DirtyReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            truncated_objects: 0,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> DirtyResult<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or("dirty stream truncated")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_u32_raw(&mut self) -> DirtyResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_i64(&mut self) -> DirtyResult<i64> {
+        let bytes = self.take(8)?;
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    /// 32-bit value followed by 4 bytes of padding
+    fn read_u32(&mut self) -> DirtyResult<u32> {
+        let value = self.read_u32_raw()?;
+        self.take(4)?;
+        Ok(value)
+    }
+
+    fn read_i32(&mut self) -> DirtyResult<i32> {
+        self.read_u32().map(|v| v as i32)
+    }
+
+    fn read_string(&mut self) -> DirtyResult<String> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.take(len)?;
+        self.take(4)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn read_len(&mut self) -> DirtyResult<u32> {
+        let len = self.read_u32()?;
+        if len > MAX_COLLECTION_LEN {
+            return Err(format!("dirty stream collection too long ({})", len).into());
+        }
+        Ok(len)
+    }
+
+    fn read_repeated_i32(&mut self) -> DirtyResult<Vec<i32>> {
+        let count = self.read_len()?;
+        (0..count).map(|_| self.read_i32()).collect()
+    }
+
+    /// Enter an object, returning the offset its body ends at
+    fn begin_object(&mut self) -> DirtyResult<usize> {
+        let tag = self.read_u32_raw()?;
+        if tag != OBJECT_BEGIN {
+            return Err(format!("expected dirty object, found tag 0x{:08X}", tag).into());
+        }
+        let body_len = self.read_u32_raw()? as usize;
+        Ok(self
+            .pos
+            .checked_add(body_len)
+            .filter(|end| *end <= self.data.len())
+            .unwrap_or(self.data.len()))
+    }
+
+    /// Next field number of the current object, None once it ends
+    fn next_field(&mut self, end: usize) -> DirtyResult<Option<u32>> {
+        if self.pos >= end {
+            self.consume_end_marker();
+            return Ok(None);
+        }
+        let field = self.read_u32_raw()?;
+        self.take(4)?;
+        if field == OBJECT_END {
+            return Ok(None);
+        }
+        Ok(Some(field))
+    }
+
+    fn peek_u32_raw(&self) -> Option<u32> {
+        let bytes = self.data.get(self.pos..self.pos + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Skip the value of an unknown field. Nested objects are skipped whole;
+    /// any other value has no length on the wire, so the rest of the current
+    /// object (ending at `end`) is skipped and false is returned.
+    fn skip_unknown(&mut self, end: usize, message: &str, field: u32) -> bool {
+        if self.peek_u32_raw() == Some(OBJECT_BEGIN) {
+            let start = self.pos;
+            if let Ok(value_end) = self.begin_object()
+                && value_end <= end
+            {
+                debug!(
+                    "[DirtyData] skipping unknown {} object field {}",
+                    message, field
+                );
+                self.pos = value_end;
+                self.consume_end_marker();
+                return true;
+            }
+            self.pos = start;
+        }
+
+        debug!(
+            "[DirtyData] unknown {} field {}, skipping rest of object",
+            message, field
+        );
+        self.truncated_objects += 1;
+        self.pos = end.max(self.pos).min(self.data.len());
+        self.consume_end_marker();
+        false
+    }
+
+    /// Consume an end marker left after the body (if the body length excluded it)
+    fn consume_end_marker(&mut self) {
+        if let Some(marker) = self.data.get(self.pos..self.pos + 8)
+            && u32::from_le_bytes([marker[0], marker[1], marker[2], marker[3]]) == OBJECT_END
+        {
+            self.pos += 8;
+        }
+    }
+}
+
+/// Apply a dirty delta to `model`, returning which sections changed
+pub fn apply_dirty_data(model: &mut CharSerialize, data: &[u8]) -> DirtyResult<DirtySections> {
+    let mut reader = DirtyReader::new(data);
+    let mut sections = DirtySections::default();
+    let end = reader.begin_object()?;
+
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            1 => model.char_id = reader.read_i64()?,
+            2 => {
+                apply_char_base(
+                    &mut reader,
+                    model.char_base.get_or_insert_with(Default::default),
+                )?;
+                sections.char_base = true;
+            }
+            3 => {
+                apply_scene_data(
+                    &mut reader,
+                    model.scene_data.get_or_insert_with(Default::default),
+                )?;
+                sections.scene_data = true;
+            }
+            7 => {
+                apply_item_package(
+                    &mut reader,
+                    model.item_package.get_or_insert_with(Default::default),
+                )?;
+                sections.modules = true;
+            }
+            57 => {
+                apply_mod(
+                    &mut reader,
+                    model.r#mod.get_or_insert_with(Default::default),
+                )?;
+                sections.modules = true;
+            }
+            61 => {
+                apply_profession_list(
+                    &mut reader,
+                    model.profession_list.get_or_insert_with(Default::default),
+                )?;
+                sections.profession = true;
+            }
+            other => {
+                if !reader.skip_unknown(end, "CharSerialize", other) {
+                    break;
+                }
+            }
+        }
+    }
+
+    sections.truncated_objects = reader.truncated_objects;
+    Ok(sections)
+}
+
+fn apply_char_base(reader: &mut DirtyReader<'_>, char_base: &mut CharBaseInfo) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            1 => char_base.char_id = reader.read_i64()?,
+            2 => char_base.account_id = reader.read_string()?,
+            5 => char_base.name = reader.read_string()?,
+            35 => char_base.fight_point = reader.read_i32()?,
+            other => {
+                if !reader.skip_unknown(end, "CharBaseInfo", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_scene_data(reader: &mut DirtyReader<'_>, scene_data: &mut SceneData) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            6 => scene_data.level_map_id = reader.read_u32()?,
+            15 => scene_data.line_id = reader.read_u32()?,
+            other => {
+                if !reader.skip_unknown(end, "SceneData", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_item_package(reader: &mut DirtyReader<'_>, package: &mut ItemPackage) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            1 => {
+                for _ in 0..reader.read_len()? {
+                    let key = reader.read_i32()?;
+                    apply_package(reader, package.packages.entry(key).or_default())?;
+                }
+            }
+            other => {
+                if !reader.skip_unknown(end, "ItemPackage", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_package(reader: &mut DirtyReader<'_>, package: &mut Package) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            4 => {
+                for _ in 0..reader.read_len()? {
+                    let key = reader.read_i64()?;
+                    apply_item(reader, package.items.entry(key).or_default())?;
+                }
+            }
+            other => {
+                if !reader.skip_unknown(end, "Package", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_item(reader: &mut DirtyReader<'_>, item: &mut Item) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            13 => apply_mod_new_attr(
+                reader,
+                item.mod_new_attr.get_or_insert_with(Default::default),
+            )?,
+            other => {
+                if !reader.skip_unknown(end, "Item", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_mod_new_attr(reader: &mut DirtyReader<'_>, attr: &mut ModNewAttr) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            1 => attr.mod_parts = reader.read_repeated_i32()?,
+            other => {
+                if !reader.skip_unknown(end, "ModNewAttr", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_mod(reader: &mut DirtyReader<'_>, module: &mut Mod) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            2 => {
+                for _ in 0..reader.read_len()? {
+                    let key = reader.read_i64()?;
+                    apply_mod_info(reader, module.mod_infos.entry(key).or_default())?;
+                }
+            }
+            other => {
+                if !reader.skip_unknown(end, "Mod", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_mod_info(reader: &mut DirtyReader<'_>, info: &mut ModInfo) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            4 => info.init_link_nums = reader.read_repeated_i32()?,
+            other => {
+                if !reader.skip_unknown(end, "ModInfo", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_profession_list(
+    reader: &mut DirtyReader<'_>,
+    list: &mut ProfessionList,
+) -> DirtyResult<()> {
+    let end = reader.begin_object()?;
+    while let Some(field) = reader.next_field(end)? {
+        match field {
+            1 => list.cur_profession_id = reader.read_i32()?,
+            other => {
+                if !reader.skip_unknown(end, "ProfessionList", other) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(value: u32) -> Vec<u8> {
+        value.to_le_bytes().to_vec()
+    }
+
+    fn int32(value: i32) -> Vec<u8> {
+        [raw(value as u32), raw(0)].concat()
+    }
+
+    fn int64(value: i64) -> Vec<u8> {
+        value.to_le_bytes().to_vec()
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        [int32(value.len() as i32), value.as_bytes().to_vec(), raw(0)].concat()
+    }
+
+    fn field(number: u32, value: Vec<u8>) -> Vec<u8> {
+        [raw(number), raw(0), value].concat()
+    }
+
+    fn object(fields: &[Vec<u8>]) -> Vec<u8> {
+        let body = fields.concat();
+        [
+            raw(OBJECT_BEGIN),
+            raw(body.len() as u32),
+            body,
+            raw(OBJECT_END),
+            raw(0),
+        ]
+        .concat()
+    }
+
+    fn profession(id: i32) -> Vec<u8> {
+        field(61, object(&[field(1, int32(id))]))
+    }
+
+    fn modules(key: i64, links: &[i32]) -> Vec<u8> {
+        let repeated = [
+            int32(links.len() as i32),
+            links.iter().flat_map(|&l| int32(l)).collect(),
+        ]
+        .concat();
+        let mod_info = object(&[field(4, repeated)]);
+        field(
+            57,
+            object(&[field(2, [int32(1), int64(key), mod_info].concat())]),
+        )
+    }
+
+    #[test]
+    fn applies_fields_in_any_order() {
+        let data = object(&[
+            profession(11),
+            field(
+                2,
+                object(&[field(35, int32(12345)), field(5, string("Tester"))]),
+            ),
+            field(1, int64(42)),
+            field(3, object(&[field(15, int32(7)), field(6, int32(2))])),
+        ]);
+        let mut model = CharSerialize::default();
+
+        let sections = apply_dirty_data(&mut model, &data).unwrap();
+
+        assert_eq!(model.char_id, 42);
+        let char_base = model.char_base.unwrap();
+        assert_eq!(char_base.name, "Tester");
+        assert_eq!(char_base.fight_point, 12345);
+        assert_eq!(model.scene_data.unwrap().line_id, 7);
+        assert_eq!(model.profession_list.unwrap().cur_profession_id, 11);
+        assert!(sections.char_base && sections.scene_data && sections.profession);
+        assert!(!sections.modules);
+        assert_eq!(sections.truncated_objects, 0);
+    }
+
+    #[test]
+    fn unknown_object_field_is_skipped() {
+        let unknown = field(20, object(&[field(1, int32(5)), field(2, string("x"))]));
+        let data = object(&[unknown, modules(9, &[3, 4]), profession(2)]);
+        let mut model = CharSerialize::default();
+
+        let sections = apply_dirty_data(&mut model, &data).unwrap();
+
+        let mod_info = &model.r#mod.unwrap().mod_infos[&9];
+        assert_eq!(mod_info.init_link_nums, vec![3, 4]);
+        assert_eq!(model.profession_list.unwrap().cur_profession_id, 2);
+        assert!(sections.modules && sections.profession);
+        assert_eq!(sections.truncated_objects, 0);
+    }
+
+    #[test]
+    fn unknown_scalar_field_only_truncates_its_object() {
+        let char_base = object(&[
+            field(5, string("Before")),
+            field(3, int32(1)),
+            field(35, int32(999)),
+        ]);
+        let data = object(&[field(2, char_base), profession(4)]);
+        let mut model = CharSerialize::default();
+
+        let sections = apply_dirty_data(&mut model, &data).unwrap();
+
+        let char_base = model.char_base.unwrap();
+        assert_eq!(char_base.name, "Before");
+        assert_eq!(char_base.fight_point, 0);
+        assert_eq!(model.profession_list.unwrap().cur_profession_id, 4);
+        assert_eq!(sections.truncated_objects, 1);
+    }
+
+    #[test]
+    fn truncated_buffer_is_an_error() {
+        let data = object(&[field(1, int64(42)), profession(3)]);
+        for len in [0, 4, 12, 20, data.len() - 20] {
+            let mut model = CharSerialize::default();
+            assert!(
+                apply_dirty_data(&mut model, &data[..len]).is_err(),
+                "accepted {} of {} bytes",
+                len,
+                data.len()
+            );
+        }
+    }
+
+    #[test]
+    fn oversized_collection_is_rejected() {
+        let map = [int32(MAX_COLLECTION_LEN as i32 + 1), int64(1)].concat();
+        let data = object(&[field(57, object(&[field(2, map)]))]);
+        let mut model = CharSerialize::default();
+        assert!(apply_dirty_data(&mut model, &data).is_err());
+    }
+}
@@ -1,3 +1,5 @@
 pub mod constants;
+pub mod dirty;
 #[rustfmt::skip]
+#[allow(clippy::enum_variant_names)]
 pub mod pb;
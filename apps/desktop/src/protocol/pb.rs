@@ -1,17 +1,47 @@
 // This file is @generated by prost-build.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ActorBodyPartInfo {
+    #[prost(int32, tag = "1")]
+    pub part_id: i32,
+    #[prost(uint64, tag = "2")]
+    pub hp: u64,
+    #[prost(uint64, tag = "3")]
+    pub max_hp: u64,
+    #[prost(enumeration = "BodyPartState", tag = "4")]
+    pub state: i32,
+    #[prost(int32, tag = "5")]
+    pub fleshy_id: i32,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ActorBodyPartInfos {
+    #[prost(int64, tag = "1")]
+    pub uuid: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub infos: ::prost::alloc::vec::Vec<ActorBodyPartInfo>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AoiSyncDelta {
     #[prost(int64, tag = "1")]
     pub uuid: i64,
     #[prost(message, optional, tag = "2")]
     pub attrs: ::core::option::Option<AttrCollection>,
+    #[prost(message, optional, tag = "6")]
+    pub body_part_infos: ::core::option::Option<ActorBodyPartInfos>,
     #[prost(message, optional, tag = "7")]
     pub skill_effects: ::core::option::Option<SkillEffect>,
+    #[prost(message, optional, tag = "10")]
+    pub buff_infos: ::core::option::Option<BuffInfoSync>,
+    #[prost(message, optional, tag = "11")]
+    pub buff_effect: ::core::option::Option<BuffEffectSync>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AoiSyncToMeDelta {
     #[prost(message, optional, tag = "1")]
     pub base_delta: ::core::option::Option<AoiSyncDelta>,
+    #[prost(int64, repeated, tag = "2")]
+    pub sync_hate_ids: ::prost::alloc::vec::Vec<i64>,
+    #[prost(message, repeated, tag = "3")]
+    pub sync_skill_c_ds: ::prost::alloc::vec::Vec<SkillCd>,
     #[prost(int64, tag = "5")]
     pub uuid: i64,
 }
@@ -29,6 +59,56 @@ pub struct AttrCollection {
     #[prost(message, repeated, tag = "2")]
     pub attrs: ::prost::alloc::vec::Vec<Attr>,
 }
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BuffEffect {
+    #[prost(enumeration = "EBuffEventType", tag = "1")]
+    pub r#type: i32,
+    #[prost(int32, tag = "2")]
+    pub buff_uuid: i32,
+    #[prost(int64, tag = "3")]
+    pub host_uuid: i64,
+    #[prost(int64, tag = "4")]
+    pub trigger_time: i64,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BuffEffectSync {
+    #[prost(int64, tag = "1")]
+    pub uuid: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub buff_effects: ::prost::alloc::vec::Vec<BuffEffect>,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BuffInfo {
+    #[prost(int32, tag = "1")]
+    pub buff_uuid: i32,
+    #[prost(int32, tag = "2")]
+    pub base_id: i32,
+    #[prost(int32, tag = "3")]
+    pub level: i32,
+    #[prost(int64, tag = "4")]
+    pub host_uuid: i64,
+    #[prost(int32, tag = "5")]
+    pub table_uuid: i32,
+    #[prost(int64, tag = "6")]
+    pub create_time: i64,
+    #[prost(int64, tag = "7")]
+    pub fire_uuid: i64,
+    #[prost(int32, tag = "8")]
+    pub layer: i32,
+    #[prost(int32, tag = "9")]
+    pub part_id: i32,
+    #[prost(int32, tag = "10")]
+    pub count: i32,
+    #[prost(int32, tag = "11")]
+    pub duration: i32,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BuffInfoSync {
+    #[prost(int64, tag = "1")]
+    pub uuid: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub buff_infos: ::prost::alloc::vec::Vec<BuffInfo>,
+}
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct CharBaseInfo {
     #[prost(int64, tag = "1")]
@@ -56,9 +136,18 @@ pub struct CharSerialize {
     pub profession_list: ::core::option::Option<ProfessionList>,
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ClientHitPartInfo {
+    #[prost(int32, tag = "1")]
+    pub part_id: i32,
+    #[prost(int64, tag = "3")]
+    pub damage_val: i64,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct DisappearEntity {
     #[prost(int64, tag = "1")]
     pub uuid: i64,
+    #[prost(enumeration = "EDisappearType", tag = "2")]
+    pub r#type: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Entity {
@@ -68,6 +157,8 @@ pub struct Entity {
     pub ent_type: i32,
     #[prost(message, optional, tag = "3")]
     pub attrs: ::core::option::Option<AttrCollection>,
+    #[prost(message, optional, tag = "5")]
+    pub body_part_infos: ::core::option::Option<ActorBodyPartInfos>,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct Item {
@@ -146,6 +237,19 @@ pub struct SceneData {
     #[prost(uint32, tag = "15")]
     pub line_id: u32,
 }
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SkillCd {
+    #[prost(int32, tag = "1")]
+    pub skill_level_id: i32,
+    #[prost(int64, tag = "2")]
+    pub begin_time: i64,
+    #[prost(int32, tag = "3")]
+    pub duration: i32,
+    #[prost(enumeration = "ESkillCdType", tag = "4")]
+    pub skill_cd_type: i32,
+    #[prost(int32, tag = "5")]
+    pub valid_cd_time: i32,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SkillEffect {
     #[prost(message, repeated, tag = "2")]
@@ -161,8 +265,15 @@ pub struct SyncContainerData {
     #[prost(message, optional, tag = "1")]
     pub v_data: ::core::option::Option<CharSerialize>,
 }
-#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SyncContainerDirtyData {
+    #[prost(bytes = "vec", tag = "1")]
+    pub v_data: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct SyncDamageInfo {
+    #[prost(enumeration = "EDamageSource", tag = "1")]
+    pub damage_source: i32,
     #[prost(bool, tag = "2")]
     pub is_miss: bool,
     #[prost(enumeration = "EDamageType", tag = "4")]
@@ -171,18 +282,28 @@ pub struct SyncDamageInfo {
     pub type_flag: i32,
     #[prost(int64, tag = "6")]
     pub value: i64,
+    #[prost(int64, tag = "7")]
+    pub actual_value: i64,
     #[prost(int64, tag = "8")]
     pub lucky_value: i64,
     #[prost(int64, tag = "9")]
     pub hp_lessen_value: i64,
+    #[prost(int64, tag = "10")]
+    pub shield_lessen_value: i64,
     #[prost(int64, tag = "11")]
     pub attacker_uuid: i64,
     #[prost(int32, tag = "12")]
     pub owner_id: i32,
     #[prost(bool, tag = "17")]
     pub is_dead: bool,
+    #[prost(enumeration = "EDamageProperty", tag = "18")]
+    pub property: i32,
+    #[prost(message, repeated, tag = "20")]
+    pub part_infos: ::prost::alloc::vec::Vec<ClientHitPartInfo>,
     #[prost(int64, tag = "21")]
     pub top_summoner_id: i64,
+    #[prost(enumeration = "EDamageMode", tag = "25")]
+    pub damage_mode: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SyncNearDeltaInfo {
@@ -196,6 +317,13 @@ pub struct SyncNearEntities {
     #[prost(message, repeated, tag = "2")]
     pub disappear: ::prost::alloc::vec::Vec<DisappearEntity>,
 }
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SyncServerTime {
+    #[prost(int64, tag = "1")]
+    pub client_milliseconds: i64,
+    #[prost(int64, tag = "2")]
+    pub server_milliseconds: i64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SyncToMeDeltaInfo {
     #[prost(message, optional, tag = "1")]
@@ -203,10 +331,197 @@ pub struct SyncToMeDeltaInfo {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum BodyPartState {
+    BodyPartStateDefault = 0,
+    BodyPartStateInjury = 1,
+    BodyPartStateDead = 2,
+}
+impl BodyPartState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::BodyPartStateDefault => "BodyPartStateDefault",
+            Self::BodyPartStateInjury => "BodyPartStateInjury",
+            Self::BodyPartStateDead => "BodyPartStateDead",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BodyPartStateDefault" => Some(Self::BodyPartStateDefault),
+            "BodyPartStateInjury" => Some(Self::BodyPartStateInjury),
+            "BodyPartStateDead" => Some(Self::BodyPartStateDead),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EBuffEventType {
+    BuffEventUnknown = 0,
+    BuffEventAddTo = 1,
+    BuffEventRemove = 2,
+    BuffEventReplace = 3,
+    BuffEventTimer = 4,
+    BuffEventStackLayer = 5,
+    BuffEventRemoveLayer = 6,
+}
+impl EBuffEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::BuffEventUnknown => "BuffEventUnknown",
+            Self::BuffEventAddTo => "BuffEventAddTo",
+            Self::BuffEventRemove => "BuffEventRemove",
+            Self::BuffEventReplace => "BuffEventReplace",
+            Self::BuffEventTimer => "BuffEventTimer",
+            Self::BuffEventStackLayer => "BuffEventStackLayer",
+            Self::BuffEventRemoveLayer => "BuffEventRemoveLayer",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BuffEventUnknown" => Some(Self::BuffEventUnknown),
+            "BuffEventAddTo" => Some(Self::BuffEventAddTo),
+            "BuffEventRemove" => Some(Self::BuffEventRemove),
+            "BuffEventReplace" => Some(Self::BuffEventReplace),
+            "BuffEventTimer" => Some(Self::BuffEventTimer),
+            "BuffEventStackLayer" => Some(Self::BuffEventStackLayer),
+            "BuffEventRemoveLayer" => Some(Self::BuffEventRemoveLayer),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EDamageMode {
+    DamageModeNormal = 0,
+    DamageModePhysical = 1,
+    DamageModeMagical = 2,
+}
+impl EDamageMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DamageModeNormal => "DamageModeNormal",
+            Self::DamageModePhysical => "DamageModePhysical",
+            Self::DamageModeMagical => "DamageModeMagical",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DamageModeNormal" => Some(Self::DamageModeNormal),
+            "DamageModePhysical" => Some(Self::DamageModePhysical),
+            "DamageModeMagical" => Some(Self::DamageModeMagical),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EDamageProperty {
+    DamagePropertyGeneral = 0,
+    DamagePropertyFire = 1,
+    DamagePropertyWater = 2,
+    DamagePropertyElectricity = 3,
+    DamagePropertyWood = 4,
+    DamagePropertyWind = 5,
+    DamagePropertyRock = 6,
+    DamagePropertyLight = 7,
+    DamagePropertyDark = 8,
+}
+impl EDamageProperty {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DamagePropertyGeneral => "DamagePropertyGeneral",
+            Self::DamagePropertyFire => "DamagePropertyFire",
+            Self::DamagePropertyWater => "DamagePropertyWater",
+            Self::DamagePropertyElectricity => "DamagePropertyElectricity",
+            Self::DamagePropertyWood => "DamagePropertyWood",
+            Self::DamagePropertyWind => "DamagePropertyWind",
+            Self::DamagePropertyRock => "DamagePropertyRock",
+            Self::DamagePropertyLight => "DamagePropertyLight",
+            Self::DamagePropertyDark => "DamagePropertyDark",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DamagePropertyGeneral" => Some(Self::DamagePropertyGeneral),
+            "DamagePropertyFire" => Some(Self::DamagePropertyFire),
+            "DamagePropertyWater" => Some(Self::DamagePropertyWater),
+            "DamagePropertyElectricity" => Some(Self::DamagePropertyElectricity),
+            "DamagePropertyWood" => Some(Self::DamagePropertyWood),
+            "DamagePropertyWind" => Some(Self::DamagePropertyWind),
+            "DamagePropertyRock" => Some(Self::DamagePropertyRock),
+            "DamagePropertyLight" => Some(Self::DamagePropertyLight),
+            "DamagePropertyDark" => Some(Self::DamagePropertyDark),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EDamageSource {
+    DamageSourceSkill = 0,
+    DamageSourceBullet = 1,
+    DamageSourceBuff = 2,
+    DamageSourceFall = 3,
+    DamageSourceFakeBullet = 4,
+    DamageSourceOther = 100,
+}
+impl EDamageSource {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DamageSourceSkill => "DamageSourceSkill",
+            Self::DamageSourceBullet => "DamageSourceBullet",
+            Self::DamageSourceBuff => "DamageSourceBuff",
+            Self::DamageSourceFall => "DamageSourceFall",
+            Self::DamageSourceFakeBullet => "DamageSourceFakeBullet",
+            Self::DamageSourceOther => "DamageSourceOther",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DamageSourceSkill" => Some(Self::DamageSourceSkill),
+            "DamageSourceBullet" => Some(Self::DamageSourceBullet),
+            "DamageSourceBuff" => Some(Self::DamageSourceBuff),
+            "DamageSourceFall" => Some(Self::DamageSourceFall),
+            "DamageSourceFakeBullet" => Some(Self::DamageSourceFakeBullet),
+            "DamageSourceOther" => Some(Self::DamageSourceOther),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum EDamageType {
     Normal = 0,
     Miss = 1,
     Heal = 2,
+    Immune = 3,
+    Fall = 4,
+    Absorbed = 5,
 }
 impl EDamageType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -218,6 +533,9 @@ impl EDamageType {
             Self::Normal => "Normal",
             Self::Miss => "Miss",
             Self::Heal => "Heal",
+            Self::Immune => "Immune",
+            Self::Fall => "Fall",
+            Self::Absorbed => "Absorbed",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -226,6 +544,44 @@ impl EDamageType {
             "Normal" => Some(Self::Normal),
             "Miss" => Some(Self::Miss),
             "Heal" => Some(Self::Heal),
+            "Immune" => Some(Self::Immune),
+            "Fall" => Some(Self::Fall),
+            "Absorbed" => Some(Self::Absorbed),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EDisappearType {
+    DisappearNormal = 0,
+    DisappearDead = 1,
+    DisappearDestroy = 2,
+    DisappearTransferLeave = 3,
+    DisappearTransferPassLineLeave = 4,
+}
+impl EDisappearType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DisappearNormal => "DisappearNormal",
+            Self::DisappearDead => "DisappearDead",
+            Self::DisappearDestroy => "DisappearDestroy",
+            Self::DisappearTransferLeave => "DisappearTransferLeave",
+            Self::DisappearTransferPassLineLeave => "DisappearTransferPassLineLeave",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DisappearNormal" => Some(Self::DisappearNormal),
+            "DisappearDead" => Some(Self::DisappearDead),
+            "DisappearDestroy" => Some(Self::DisappearDestroy),
+            "DisappearTransferLeave" => Some(Self::DisappearTransferLeave),
+            "DisappearTransferPassLineLeave" => Some(Self::DisappearTransferPassLineLeave),
             _ => None,
         }
     }
@@ -235,7 +591,16 @@ impl EDamageType {
 pub enum EEntityType {
     EntErrType = 0,
     EntMonster = 1,
+    EntNpc = 2,
+    EntSceneObject = 3,
+    EntZone = 5,
+    EntBullet = 6,
+    EntClientBullet = 7,
+    EntPet = 8,
     EntChar = 10,
+    EntDummy = 11,
+    EntField = 14,
+    EntTrap = 15,
 }
 impl EEntityType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -246,7 +611,16 @@ impl EEntityType {
         match self {
             Self::EntErrType => "EntErrType",
             Self::EntMonster => "EntMonster",
+            Self::EntNpc => "EntNpc",
+            Self::EntSceneObject => "EntSceneObject",
+            Self::EntZone => "EntZone",
+            Self::EntBullet => "EntBullet",
+            Self::EntClientBullet => "EntClientBullet",
+            Self::EntPet => "EntPet",
             Self::EntChar => "EntChar",
+            Self::EntDummy => "EntDummy",
+            Self::EntField => "EntField",
+            Self::EntTrap => "EntTrap",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -254,7 +628,42 @@ impl EEntityType {
         match value {
             "EntErrType" => Some(Self::EntErrType),
             "EntMonster" => Some(Self::EntMonster),
+            "EntNpc" => Some(Self::EntNpc),
+            "EntSceneObject" => Some(Self::EntSceneObject),
+            "EntZone" => Some(Self::EntZone),
+            "EntBullet" => Some(Self::EntBullet),
+            "EntClientBullet" => Some(Self::EntClientBullet),
+            "EntPet" => Some(Self::EntPet),
             "EntChar" => Some(Self::EntChar),
+            "EntDummy" => Some(Self::EntDummy),
+            "EntField" => Some(Self::EntField),
+            "EntTrap" => Some(Self::EntTrap),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ESkillCdType {
+    SkillCdNormal = 0,
+    SkillCdCharge = 1,
+}
+impl ESkillCdType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::SkillCdNormal => "SkillCdNormal",
+            Self::SkillCdCharge => "SkillCdCharge",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SkillCdNormal" => Some(Self::SkillCdNormal),
+            "SkillCdCharge" => Some(Self::SkillCdCharge),
             _ => None,
         }
     }
@@ -0,0 +1,338 @@
+use crate::models::{PlayerInfoCache, SkillStats};
+use crate::stats::{Encounter, EncounterEndReason, MAX_ARCHIVED_ENCOUNTERS};
+use chrono::{DateTime, Local, TimeZone};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// File finished encounters are appended to, one JSON object per line
+pub fn archive_path() -> PathBuf {
+    crate::config::get_data_dir().join("encounters.jsonl")
+}
+
+/// Per-skill totals of an archived player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSkill {
+    pub skill_id: i32,
+    pub total: f32,
+    pub hits: u32,
+    pub crit_rate: f32,
+    pub lucky_rate: f32,
+    pub max_hit: f32,
+}
+
+/// Skills by total, largest first
+fn archived_skills(skills: &HashMap<i32, SkillStats>) -> Vec<ArchivedSkill> {
+    let mut skills: Vec<ArchivedSkill> = skills
+        .iter()
+        .map(|(skill_id, stats)| ArchivedSkill {
+            skill_id: *skill_id,
+            total: stats.total,
+            hits: stats.hits,
+            crit_rate: stats.crit_rate(),
+            lucky_rate: stats.lucky_rate(),
+            max_hit: stats.max_hit,
+        })
+        .collect();
+    skills.sort_by(|a, b| {
+        b.total
+            .partial_cmp(&a.total)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    skills
+}
+
+/// Per-player totals of an archived encounter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedPlayer {
+    pub uid: i64,
+    pub name: String,
+    pub class_id: Option<i32>,
+    pub ability_score: Option<i32>,
+    pub dps: f32,
+    pub total_damage: f32,
+    pub max_hit: f32,
+    pub crit_rate: f32,
+    pub lucky_rate: f32,
+    pub total_healing: f32,
+    pub total_damage_taken: f32,
+    pub effective_damage: f32,
+    pub overkill_damage: f32,
+    pub damage_absorbed: f32,
+    /// Damage per second since the first hit of the encounter
+    #[serde(default)]
+    pub dps_timeline: Vec<f32>,
+    #[serde(default)]
+    pub damage_skills: Vec<ArchivedSkill>,
+    #[serde(default)]
+    pub healing_skills: Vec<ArchivedSkill>,
+}
+
+/// Encounter as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEncounter {
+    /// Local start time (ms since epoch)
+    pub started_at_ms: i64,
+    pub duration_secs: f32,
+    pub boss_base_id: Option<u32>,
+    pub boss_name: Option<String>,
+    pub end_reason: EncounterEndReason,
+    pub line_id: Option<u32>,
+    pub level_map_id: Option<u32>,
+    pub local_uid: Option<i64>,
+    pub players: Vec<ArchivedPlayer>,
+}
+
+fn rate(hits: u32, total: u32) -> f32 {
+    if total > 0 {
+        (hits as f32 / total as f32) * 100.0
+    } else {
+        0.0
+    }
+}
+
+impl ArchivedEncounter {
+    /// Players who dealt, healed or took damage, most damage first
+    pub fn from_encounter(encounter: &Encounter, info_cache: &PlayerInfoCache) -> Self {
+        let duration = encounter.duration_secs.round().max(1.0);
+        let mut players: Vec<ArchivedPlayer> = encounter
+            .players
+            .values()
+            .filter(|p| p.total_damage > 0.0 || p.total_healing > 0.0 || p.total_damage_taken > 0.0)
+            .map(|p| {
+                let metadata = info_cache.get(p.uid);
+                ArchivedPlayer {
+                    uid: p.uid,
+                    name: metadata.name.unwrap_or_else(|| p.name.clone()),
+                    class_id: metadata.class_id,
+                    ability_score: metadata.ability_score,
+                    dps: p.total_damage / duration,
+                    total_damage: p.total_damage,
+                    max_hit: p.max_single_hit,
+                    crit_rate: rate(p.critical_hits, p.total_hits),
+                    lucky_rate: rate(p.lucky_hits, p.total_hits),
+                    total_healing: p.total_healing,
+                    total_damage_taken: p.total_damage_taken,
+                    effective_damage: p.effective_damage,
+                    overkill_damage: p.overkill_damage,
+                    damage_absorbed: p.total_damage_absorbed,
+                    dps_timeline: encounter.timeline.get(&p.uid).cloned().unwrap_or_default(),
+                    damage_skills: archived_skills(&p.damage_skills),
+                    healing_skills: archived_skills(&p.healing_skills),
+                }
+            })
+            .collect();
+        players.sort_by(|a, b| {
+            b.total_damage
+                .partial_cmp(&a.total_damage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Self {
+            started_at_ms: encounter.started_at.timestamp_millis(),
+            duration_secs: encounter.duration_secs,
+            boss_base_id: encounter.boss_base_id,
+            boss_name: encounter.boss_name(),
+            end_reason: encounter.end_reason,
+            line_id: encounter.line_id,
+            level_map_id: encounter.level_map_id,
+            local_uid: encounter.local_uid,
+            players,
+        }
+    }
+
+    pub fn started_at(&self) -> Option<DateTime<Local>> {
+        Local.timestamp_millis_opt(self.started_at_ms).single()
+    }
+
+    pub fn total_damage(&self) -> f32 {
+        self.players.iter().map(|p| p.total_damage).sum()
+    }
+
+    pub fn party_dps(&self) -> f32 {
+        self.total_damage() / self.duration_secs.round().max(1.0)
+    }
+
+    /// Party damage per second since the first hit
+    pub fn party_timeline(&self) -> Vec<f32> {
+        let len = self
+            .players
+            .iter()
+            .map(|p| p.dps_timeline.len())
+            .max()
+            .unwrap_or(0);
+        let mut timeline = vec![0.0; len];
+        for player in &self.players {
+            for (second, damage) in player.dps_timeline.iter().enumerate() {
+                timeline[second] += damage;
+            }
+        }
+        timeline
+    }
+}
+
+/// Append one encounter to the archive file
+pub fn append(encounter: &ArchivedEncounter) -> io::Result<()> {
+    append_to(&archive_path(), encounter)
+}
+
+fn append_to(path: &Path, encounter: &ArchivedEncounter) -> io::Result<()> {
+    let line = serde_json::to_string(encounter)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// The newest `MAX_ARCHIVED_ENCOUNTERS` archived encounters, oldest first.
+/// Unreadable lines are skipped.
+pub fn load_all() -> Vec<ArchivedEncounter> {
+    load_from(&archive_path(), MAX_ARCHIVED_ENCOUNTERS)
+}
+
+fn load_from(path: &Path, limit: usize) -> Vec<ArchivedEncounter> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            warn!("Failed to read encounter archive {:?}: {}", path, e);
+            return Vec::new();
+        }
+    };
+
+    // Read line by line so a large archive never sits in memory whole
+    let mut encounters = VecDeque::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                warn!("Skipping encounter archive line {}: {}", index + 1, e);
+                continue;
+            }
+            Err(e) => {
+                warn!("Failed to read encounter archive {:?}: {}", path, e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<ArchivedEncounter>(&line) {
+            Ok(encounter) => {
+                if encounters.len() >= limit {
+                    encounters.pop_front();
+                }
+                encounters.push_back(encounter);
+            }
+            Err(e) => warn!("Skipping encounter archive line {}: {}", index + 1, e),
+        }
+    }
+    encounters.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_archive_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "bptimer-test-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn encounter(started_at_ms: i64) -> ArchivedEncounter {
+        ArchivedEncounter {
+            started_at_ms,
+            duration_secs: 12.5,
+            boss_base_id: Some(10007),
+            boss_name: Some("Storm Goblin King".to_string()),
+            end_reason: EncounterEndReason::BossDeath,
+            line_id: Some(7),
+            level_map_id: Some(10),
+            local_uid: Some(1),
+            players: vec![ArchivedPlayer {
+                uid: 1,
+                name: "Tester".to_string(),
+                class_id: Some(11),
+                ability_score: Some(12345),
+                dps: 80.0,
+                total_damage: 1000.0,
+                max_hit: 300.0,
+                crit_rate: 25.0,
+                lucky_rate: 10.0,
+                total_healing: 50.0,
+                total_damage_taken: 200.0,
+                effective_damage: 900.0,
+                overkill_damage: 100.0,
+                damage_absorbed: 20.0,
+                dps_timeline: vec![100.0, 0.0, 900.0],
+                damage_skills: Vec::new(),
+                healing_skills: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn appended_encounters_load_back_in_order() {
+        let path = temp_archive_path("roundtrip");
+        append_to(&path, &encounter(1_000)).unwrap();
+        append_to(&path, &encounter(2_000)).unwrap();
+
+        let loaded = load_from(&path, MAX_ARCHIVED_ENCOUNTERS);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].started_at_ms, 1_000);
+        assert_eq!(loaded[1].started_at_ms, 2_000);
+        let first = &loaded[0];
+        assert_eq!(first.boss_base_id, Some(10007));
+        assert_eq!(first.end_reason, EncounterEndReason::BossDeath);
+        assert_eq!(first.players.len(), 1);
+        assert_eq!(first.players[0].name, "Tester");
+        assert_eq!(first.players[0].effective_damage, 900.0);
+        assert_eq!(first.party_timeline(), vec![100.0, 0.0, 900.0]);
+    }
+
+    #[test]
+    fn malformed_and_truncated_lines_are_skipped() {
+        let path = temp_archive_path("malformed");
+        let valid = serde_json::to_string(&encounter(1_000)).unwrap();
+        let truncated = &valid[..valid.len() / 2];
+        let mut content = format!("{}\nnot json\n\n{{\"started_at_ms\":5}}\n", valid);
+        content.push_str(&serde_json::to_string(&encounter(2_000)).unwrap());
+        content.push('\n');
+        // A crash mid-append leaves an unterminated partial line
+        content.push_str(truncated);
+        fs::write(&path, content).unwrap();
+
+        let loaded = load_from(&path, MAX_ARCHIVED_ENCOUNTERS);
+        fs::remove_file(&path).ok();
+
+        let started: Vec<i64> = loaded.iter().map(|e| e.started_at_ms).collect();
+        assert_eq!(started, vec![1_000, 2_000]);
+    }
+
+    #[test]
+    fn load_keeps_newest_encounters_up_to_limit() {
+        let path = temp_archive_path("limit");
+        for started_at_ms in 1..=5 {
+            append_to(&path, &encounter(started_at_ms)).unwrap();
+        }
+
+        let loaded = load_from(&path, 3);
+        fs::remove_file(&path).ok();
+
+        let started: Vec<i64> = loaded.iter().map(|e| e.started_at_ms).collect();
+        assert_eq!(started, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn missing_archive_loads_empty() {
+        let path = temp_archive_path("missing");
+        assert!(load_from(&path, MAX_ARCHIVED_ENCOUNTERS).is_empty());
+    }
+}
@@ -6,7 +6,7 @@ pub fn update_realtime_dps(
     player_stats: &mut std::collections::HashMap<i64, PlayerStats>,
     dps_value: &mut f32,
     max_dps: &mut f32,
-    dps_history: &mut Vec<f32>,
+    dps_history: &mut [f32],
 ) {
     let now = Instant::now();
     let mut total_dps = 0.0;
@@ -0,0 +1,403 @@
+use crate::models::cooldown::CooldownTracker;
+use crate::models::{PlayerState, PlayerStats};
+use crate::stats::{MAX_ENCOUNTER_HISTORY, carry_active_buffs, end_active_buffs};
+use crate::utils::constants;
+use chrono::{DateTime, Local};
+use instant::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Why an encounter ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EncounterEndReason {
+    Idle,
+    BossDeath,
+    ServerChange,
+    /// Snapshot of an encounter that has not ended yet
+    InProgress,
+}
+
+/// Frozen stats of one finished pull
+#[derive(Debug, Clone)]
+pub struct Encounter {
+    pub id: u32,
+    pub started_at: DateTime<Local>,
+    /// First to last hit
+    pub duration_secs: f32,
+    /// Tracked boss the party hit, if any
+    pub boss_base_id: Option<u32>,
+    pub end_reason: EncounterEndReason,
+    pub line_id: Option<u32>,
+    pub level_map_id: Option<u32>,
+    pub local_uid: Option<i64>,
+    pub players: HashMap<i64, PlayerStats>,
+    /// Damage per second since the first hit, per player uid
+    pub timeline: HashMap<i64, Vec<f32>>,
+    /// Local player's cooldown analysis at the end of the pull
+    pub cooldowns: CooldownTracker,
+}
+
+impl Encounter {
+    pub fn total_damage(&self) -> f32 {
+        self.players.values().map(|p| p.total_damage).sum()
+    }
+
+    pub fn boss_name(&self) -> Option<String> {
+        self.boss_base_id.map(|base_id| {
+            constants::get_mob_name(base_id).unwrap_or_else(|| format!("Monster {}", base_id))
+        })
+    }
+
+    /// Short label for pickers, e.g. "#3 21:04:11 Golden Juggernaut 02:31 (kill)"
+    pub fn label(&self) -> String {
+        let secs = self.duration_secs.round() as i64;
+        let mut label = format!(
+            "#{} {} {} {:02}:{:02}",
+            self.id,
+            self.started_at.format("%H:%M:%S"),
+            self.boss_name().unwrap_or_else(|| "Trash".to_string()),
+            secs / 60,
+            secs % 60
+        );
+        if self.end_reason == EncounterEndReason::BossDeath {
+            label.push_str(" (kill)");
+        }
+        label
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveEncounter {
+    id: u32,
+    started_at: DateTime<Local>,
+    first_hit: Instant,
+    last_hit: Instant,
+    boss_base_id: Option<u32>,
+    timeline: HashMap<i64, Vec<f32>>,
+}
+
+impl ActiveEncounter {
+    fn freeze(
+        &self,
+        reason: EncounterEndReason,
+        players: HashMap<i64, PlayerStats>,
+        player_state: &PlayerState,
+    ) -> Encounter {
+        Encounter {
+            id: self.id,
+            started_at: self.started_at,
+            duration_secs: self.last_hit.duration_since(self.first_hit).as_secs_f32(),
+            boss_base_id: self.boss_base_id,
+            end_reason: reason,
+            line_id: player_state.line_id,
+            level_map_id: player_state.level_map_id,
+            local_uid: player_state.uid,
+            players,
+            timeline: self.timeline.clone(),
+            cooldowns: player_state.cooldowns.clone(),
+        }
+    }
+}
+
+/// Splits combat into encounters: one starts on the first hit and ends on
+/// idle or a boss death, freezing a snapshot of every player's stats.
+/// Encounter stats are kept apart from the live meter, which keeps
+/// accumulating across pulls until it is cleared.
+#[derive(Debug, Clone)]
+pub struct EncounterEngine {
+    active: Option<ActiveEncounter>,
+    /// Player stats since the active encounter started
+    players: HashMap<i64, PlayerStats>,
+    history: VecDeque<Encounter>,
+    next_id: u32,
+}
+
+impl Default for EncounterEngine {
+    fn default() -> Self {
+        Self {
+            active: None,
+            players: HashMap::new(),
+            history: VecDeque::new(),
+            next_id: 1,
+        }
+    }
+}
+
+impl EncounterEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a hit dealt by a player. Returns true if it started a new encounter.
+    pub fn record_hit(&mut self, now: Instant) -> bool {
+        if let Some(active) = self.active.as_mut() {
+            active.last_hit = now;
+            return false;
+        }
+        self.active = Some(ActiveEncounter {
+            id: self.next_id,
+            started_at: Local::now(),
+            first_hit: now,
+            last_hit: now,
+            boss_base_id: None,
+            timeline: HashMap::new(),
+        });
+        self.next_id += 1;
+        self.reset_players();
+        true
+    }
+
+    /// Encounter stats of a player, created with `name` on first use
+    pub fn player_stats_mut(
+        &mut self,
+        player_uid: i64,
+        name: impl FnOnce() -> String,
+    ) -> &mut PlayerStats {
+        self.players.entry(player_uid).or_insert_with(|| {
+            let mut stats = PlayerStats::new(player_uid);
+            stats.name = name();
+            stats
+        })
+    }
+
+    /// Encounter stats of a player, if any were recorded
+    pub fn get_player_stats_mut(&mut self, player_uid: i64) -> Option<&mut PlayerStats> {
+        self.players.get_mut(&player_uid)
+    }
+
+    /// Start over with fresh stats, keeping buffs still running. Returns the
+    /// previous stats.
+    fn reset_players(&mut self) -> HashMap<i64, PlayerStats> {
+        let next = self
+            .players
+            .iter()
+            .filter(|(_, stats)| !stats.active_buffs.is_empty())
+            .map(|(uid, stats)| (*uid, carry_active_buffs(stats)))
+            .collect();
+        std::mem::replace(&mut self.players, next)
+    }
+
+    /// Keep the active encounter alive without starting one (e.g. damage taken)
+    pub fn touch(&mut self, now: Instant) {
+        if let Some(active) = self.active.as_mut() {
+            active.last_hit = now;
+        }
+    }
+
+    /// Add a player's damage to the active encounter's per-second timeline
+    pub fn record_damage(&mut self, player_uid: i64, damage: i64, now: Instant) {
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+        let second = now.duration_since(active.first_hit).as_secs() as usize;
+        let timeline = active.timeline.entry(player_uid).or_default();
+        if timeline.len() <= second {
+            timeline.resize(second + 1, 0.0);
+        }
+        timeline[second] += damage as f32;
+    }
+
+    /// Remember the first tracked boss hit during the active encounter
+    pub fn note_target(&mut self, base_id: Option<u32>) {
+        if let (Some(active), Some(base_id)) = (self.active.as_mut(), base_id)
+            && active.boss_base_id.is_none()
+            && constants::is_tracked_mob(base_id)
+        {
+            active.boss_base_id = Some(base_id);
+        }
+    }
+
+    /// No hit for `idle_secs`
+    pub fn is_idle(&self, now: Instant, idle_secs: f32) -> bool {
+        self.active
+            .as_ref()
+            .is_some_and(|active| now.duration_since(active.last_hit).as_secs_f32() >= idle_secs)
+    }
+
+    /// End the active encounter and archive a snapshot of its players.
+    /// Encounters in which nobody dealt damage are dropped.
+    pub fn finish(
+        &mut self,
+        reason: EncounterEndReason,
+        player_state: &PlayerState,
+    ) -> Option<&Encounter> {
+        let active = self.active.take()?;
+        let mut players = self.reset_players();
+        if !players.values().any(|p| p.total_damage > 0.0) {
+            return None;
+        }
+
+        for stats in players.values_mut() {
+            end_active_buffs(stats);
+        }
+
+        if self.history.len() >= MAX_ENCOUNTER_HISTORY {
+            self.history.pop_front();
+        }
+        self.history
+            .push_back(active.freeze(reason, players, player_state));
+        self.history.back()
+    }
+
+    /// Copy of the active encounter so far, without ending it
+    pub fn snapshot(&self, player_state: &PlayerState) -> Option<Encounter> {
+        let active = self.active.as_ref()?;
+        Some(active.freeze(
+            EncounterEndReason::InProgress,
+            self.players.clone(),
+            player_state,
+        ))
+    }
+
+    /// Drop the active encounter without archiving it
+    pub fn abort(&mut self) {
+        self.active = None;
+        self.reset_players();
+    }
+
+    /// Finished encounters, oldest first
+    pub fn history(&self) -> &VecDeque<Encounter> {
+        &self.history
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Encounter> {
+        self.history.iter().find(|e| e.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ActiveBuff;
+    use std::time::Duration;
+
+    /// Storm Goblin King, a fallback tracked mob
+    const BOSS_BASE_ID: u32 = 10007;
+
+    fn hit(engine: &mut EncounterEngine, now: Instant, player_uid: i64, damage: f32) -> bool {
+        let started = engine.record_hit(now);
+        engine
+            .player_stats_mut(player_uid, || format!("Player {}", player_uid))
+            .total_damage += damage;
+        started
+    }
+
+    #[test]
+    fn idle_gap_splits_encounters() {
+        let mut engine = EncounterEngine::new();
+        let state = PlayerState::new();
+        let start = Instant::now();
+
+        assert!(hit(&mut engine, start, 1, 100.0));
+        assert!(!hit(&mut engine, start + Duration::from_secs(4), 1, 50.0));
+        assert!(!engine.is_idle(start + Duration::from_secs(10), 10.0));
+        assert!(engine.is_idle(start + Duration::from_secs(14), 10.0));
+
+        let first = engine.finish(EncounterEndReason::Idle, &state).unwrap();
+        assert_eq!(first.id, 1);
+        assert_eq!(first.end_reason, EncounterEndReason::Idle);
+        assert_eq!(first.duration_secs, 4.0);
+        assert_eq!(first.total_damage(), 150.0);
+        assert!(!engine.is_idle(start + Duration::from_secs(60), 10.0));
+
+        assert!(hit(&mut engine, start + Duration::from_secs(60), 1, 10.0));
+        let second = engine.finish(EncounterEndReason::Idle, &state).unwrap();
+        assert_eq!(second.id, 2);
+        assert_eq!(second.total_damage(), 10.0);
+        assert_eq!(engine.history().len(), 2);
+    }
+
+    #[test]
+    fn boss_death_ends_encounter_with_boss() {
+        let mut engine = EncounterEngine::new();
+        let state = PlayerState::new();
+        let now = Instant::now();
+
+        hit(&mut engine, now, 1, 100.0);
+        engine.note_target(Some(1));
+        engine.note_target(Some(BOSS_BASE_ID));
+        hit(&mut engine, now, 2, 200.0);
+
+        let encounter = engine
+            .finish(EncounterEndReason::BossDeath, &state)
+            .unwrap();
+        assert_eq!(encounter.boss_base_id, Some(BOSS_BASE_ID));
+        assert_eq!(encounter.players.len(), 2);
+        assert!(encounter.label().ends_with("(kill)"));
+
+        // The next hit opens a fresh encounter with no stats carried over
+        assert!(hit(&mut engine, now, 1, 5.0));
+        assert_eq!(engine.get_player_stats_mut(1).unwrap().total_damage, 5.0);
+        assert!(engine.get_player_stats_mut(2).is_none());
+    }
+
+    #[test]
+    fn running_buffs_carry_over_to_next_encounter() {
+        let mut engine = EncounterEngine::new();
+        let state = PlayerState::new();
+        let now = Instant::now();
+
+        hit(&mut engine, now, 1, 100.0);
+        hit(&mut engine, now, 2, 100.0);
+        let buff = ActiveBuff {
+            base_id: 42,
+            layer: 2,
+            duration_ms: 0,
+            caster_uuid: 7,
+        };
+        let stats = engine.get_player_stats_mut(1).unwrap();
+        stats
+            .buffs
+            .entry(buff.base_id)
+            .or_default()
+            .start(&buff, now);
+        stats.active_buffs.insert(9, buff);
+
+        let encounter = engine.finish(EncounterEndReason::Idle, &state).unwrap();
+        assert!(encounter.players[&1].active_buffs.is_empty());
+        assert!(!encounter.players[&1].buffs[&42].is_active());
+
+        hit(&mut engine, now, 1, 1.0);
+        let carried = engine.get_player_stats_mut(1).unwrap();
+        assert_eq!(carried.total_damage, 1.0);
+        assert_eq!(carried.name, "Player 1");
+        assert!(carried.active_buffs.contains_key(&9));
+        assert!(carried.buffs[&42].is_active());
+        assert_eq!(carried.buffs[&42].applications, 1);
+        assert!(engine.get_player_stats_mut(2).is_none());
+    }
+
+    #[test]
+    fn encounters_without_damage_are_dropped() {
+        let mut engine = EncounterEngine::new();
+        let state = PlayerState::new();
+        let now = Instant::now();
+
+        hit(&mut engine, now, 1, 0.0);
+        assert!(engine.finish(EncounterEndReason::Idle, &state).is_none());
+        assert!(engine.history().is_empty());
+        assert!(engine.finish(EncounterEndReason::Idle, &state).is_none());
+
+        hit(&mut engine, now, 1, 10.0);
+        let encounter = engine.finish(EncounterEndReason::Idle, &state).unwrap();
+        assert_eq!(encounter.id, 2);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut engine = EncounterEngine::new();
+        let state = PlayerState::new();
+        let now = Instant::now();
+
+        for _ in 0..MAX_ENCOUNTER_HISTORY + 5 {
+            hit(&mut engine, now, 1, 10.0);
+            engine.finish(EncounterEndReason::Idle, &state);
+        }
+
+        let history = engine.history();
+        assert_eq!(history.len(), MAX_ENCOUNTER_HISTORY);
+        assert_eq!(history.front().unwrap().id, 6);
+        assert_eq!(history.back().unwrap().id, MAX_ENCOUNTER_HISTORY as u32 + 5);
+        assert!(engine.get(5).is_none());
+    }
+}
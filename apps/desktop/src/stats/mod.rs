@@ -1,11 +1,29 @@
+pub mod archive;
 pub mod calculator;
+pub mod encounter;
 pub mod processor;
 
 pub use calculator::update_realtime_dps;
-pub use processor::{process_damage_hit, process_damage_taken_hit, process_healing_hit};
+pub use encounter::{Encounter, EncounterEndReason, EncounterEngine};
+pub use processor::{
+    carry_active_buffs, end_active_buffs, process_buff_update, process_damage_hit,
+    process_damage_taken_hit, process_healing_hit, process_healing_received,
+};
 
 // Stats calculation constants
 pub const DAMAGE_WINDOW_MAX_SIZE: usize = 1000;
 pub const DAMAGE_WINDOW_RETENTION_SECS: f64 = 2.0;
 pub const DPS_WINDOW_SECS: f64 = 1.0;
 pub const DPS_HISTORY_INDEX: usize = 599;
+
+// Death recap constants
+pub const DEATH_RECAP_WINDOW_SECS: f64 = 10.0;
+pub const DEATH_RECAP_MAX_EVENTS: usize = 200;
+pub const MAX_DEATH_RECAPS: usize = 50;
+/// Hits flagged dead this close to a recorded death belong to the same death
+pub const DEATH_RECAP_MERGE_SECS: f64 = 1.0;
+
+// Encounter constants
+pub const MAX_ENCOUNTER_HISTORY: usize = 100;
+/// Newest archived encounters kept in memory; older lines stay on disk
+pub const MAX_ARCHIVED_ENCOUNTERS: usize = 2000;
@@ -1,6 +1,13 @@
-use crate::models::events::{DamageHit, DamageTakenHit, HealingHit};
-use crate::models::{DamageEntry, DamageTakenEntry, HealingEntry, PlayerStats};
-use crate::stats::{DAMAGE_WINDOW_MAX_SIZE, DAMAGE_WINDOW_RETENTION_SECS};
+use crate::models::events::{BuffChange, BuffUpdate, DamageHit, DamageTakenHit, HealingHit};
+use crate::models::{
+    ActiveBuff, DamageEntry, DamageSource, DamageTakenEntry, HealingEntry, PlayerStats,
+};
+use crate::models::{DeathRecap, RecapEvent, RecapEventKind};
+use crate::protocol::constants::entity;
+use crate::stats::{
+    DAMAGE_WINDOW_MAX_SIZE, DAMAGE_WINDOW_RETENTION_SECS, DEATH_RECAP_MAX_EVENTS,
+    DEATH_RECAP_MERGE_SECS, DEATH_RECAP_WINDOW_SECS, MAX_DEATH_RECAPS,
+};
 use instant::Instant;
 
 pub fn process_damage_hit(
@@ -12,12 +19,12 @@ pub fn process_damage_hit(
     let now = Instant::now();
     let value = hit.damage as f32;
 
-    if let Some(last) = stats.last_damage_time {
-        if now.duration_since(last).as_secs_f32() > cutoff_seconds {
-            stats.first_damage_time = None;
-            stats.last_damage_time = None;
-            stats.dps_session_start_damage = stats.total_damage;
-        }
+    if let Some(last) = stats.last_damage_time
+        && now.duration_since(last).as_secs_f32() > cutoff_seconds
+    {
+        stats.first_damage_time = None;
+        stats.last_damage_time = None;
+        stats.dps_session_start_damage = stats.total_damage;
     }
 
     if stats.first_damage_time.is_none() {
@@ -28,11 +35,47 @@ pub fn process_damage_hit(
     *total_damage += value;
     stats.total_damage += value;
     stats.total_hits += 1;
+    stats.effective_damage += hit.effective_damage as f32;
+    stats.overkill_damage += hit.overkill as f32;
 
     if value > stats.max_single_hit {
         stats.max_single_hit = value;
     }
 
+    stats
+        .damage_skills
+        .entry(hit.skill_id)
+        .or_default()
+        .record(value, hit.is_crit, hit.is_lucky);
+
+    stats
+        .damage_sources
+        .entry(DamageSource {
+            entity_type: hit.attacker_entity_type,
+            base_id: hit.attacker_base_id,
+        })
+        .or_default()
+        .record(value, hit.is_crit, hit.is_lucky);
+
+    for (breakdown, key) in [
+        (&mut stats.damage_elements, hit.property),
+        (&mut stats.damage_modes, hit.damage_mode),
+        (&mut stats.damage_source_types, hit.damage_source),
+    ] {
+        breakdown
+            .entry(key)
+            .or_default()
+            .record(value, hit.is_crit, hit.is_lucky);
+    }
+
+    let target = stats.damage_targets.entry(hit.target_uuid).or_default();
+    if hit.target_base_id.is_some() {
+        target.base_id = hit.target_base_id;
+    }
+    target.record(value, hit.is_crit, hit.is_lucky, now);
+    target.effective_damage += hit.effective_damage as f32;
+    target.overkill_damage += hit.overkill as f32;
+
     if hit.is_crit && hit.is_lucky {
         stats.crit_lucky_damage += value;
         stats.crit_lucky_hits += 1;
@@ -75,6 +118,12 @@ pub fn process_healing_hit(stats: &mut PlayerStats, hit: HealingHit) {
         stats.max_single_heal = value;
     }
 
+    stats
+        .healing_skills
+        .entry(hit.skill_id)
+        .or_default()
+        .record(value, hit.is_crit, hit.is_lucky);
+
     if hit.is_crit && hit.is_lucky {
         stats.crit_lucky_healing += value;
         stats.crit_lucky_heals += 1;
@@ -94,6 +143,53 @@ pub fn process_healing_hit(stats: &mut PlayerStats, hit: HealingHit) {
     });
 }
 
+/// Heal received by `stats`' player, kept for death recaps
+pub fn process_healing_received(stats: &mut PlayerStats, hit: &HealingHit) {
+    push_recent_incoming(
+        stats,
+        RecapEvent {
+            timestamp: Instant::now(),
+            kind: RecapEventKind::Heal,
+            source_uuid: entity::get_player_uuid(hit.player_uid),
+            source_base_id: None,
+            skill_id: hit.skill_id,
+            amount: hit.healing,
+            absorbed: 0,
+        },
+    );
+}
+
+fn push_recent_incoming(stats: &mut PlayerStats, event: RecapEvent) {
+    let now = event.timestamp;
+    stats.recent_incoming.push_back(event);
+    while let Some(oldest) = stats.recent_incoming.front() {
+        let expired = now.duration_since(oldest.timestamp).as_secs_f64() > DEATH_RECAP_WINDOW_SECS;
+        if !expired && stats.recent_incoming.len() <= DEATH_RECAP_MAX_EVENTS {
+            break;
+        }
+        stats.recent_incoming.pop_front();
+    }
+}
+
+fn record_death(stats: &mut PlayerStats, now: Instant) {
+    // Several hits of the same death can carry is_dead
+    if let Some(last) = stats.death_recaps.last_mut()
+        && now.duration_since(last.died_at).as_secs_f64() < DEATH_RECAP_MERGE_SECS
+    {
+        last.events.extend(stats.recent_incoming.drain(..));
+        return;
+    }
+
+    stats.death_recaps.push(DeathRecap {
+        died_at: now,
+        wall_time: chrono::Local::now(),
+        events: stats.recent_incoming.drain(..).collect(),
+    });
+    if stats.death_recaps.len() > MAX_DEATH_RECAPS {
+        stats.death_recaps.remove(0);
+    }
+}
+
 pub fn process_damage_taken_hit(stats: &mut PlayerStats, hit: DamageTakenHit) {
     let now = Instant::now();
 
@@ -106,6 +202,24 @@ pub fn process_damage_taken_hit(stats: &mut PlayerStats, hit: DamageTakenHit) {
         stats.death_count += 1;
     }
 
+    push_recent_incoming(
+        stats,
+        RecapEvent {
+            timestamp: now,
+            kind: RecapEventKind::Hit,
+            source_uuid: hit.attacker_uuid,
+            source_base_id: hit.attacker_base_id,
+            skill_id: hit.skill_id,
+            amount: hit.hp_lessen,
+            absorbed: hit.shield_lessen,
+        },
+    );
+    if hit.is_dead {
+        record_death(stats, now);
+    }
+
+    stats.total_damage_absorbed += hit.shield_lessen as f32;
+
     if stats.first_damage_taken_time.is_none() {
         stats.first_damage_taken_time = Some(now);
     }
@@ -124,3 +238,80 @@ pub fn process_damage_taken_hit(stats: &mut PlayerStats, hit: DamageTakenHit) {
         damage: hit.hp_lessen,
     });
 }
+
+pub fn process_buff_update(stats: &mut PlayerStats, update: BuffUpdate) {
+    let now = Instant::now();
+
+    match update.change {
+        BuffChange::Applied {
+            base_id,
+            layer,
+            duration_ms,
+            caster_uuid,
+        } => {
+            // Re-sync of a buff we already track (e.g. new stack count)
+            if let Some(active) = stats.active_buffs.get_mut(&update.buff_uuid) {
+                active.layer = layer;
+                let uptime = stats.buffs.entry(active.base_id).or_default();
+                uptime.set_layer(layer, now);
+                return;
+            }
+            let buff = ActiveBuff {
+                base_id,
+                layer,
+                duration_ms,
+                caster_uuid,
+            };
+            stats.buffs.entry(base_id).or_default().start(&buff, now);
+            stats.active_buffs.insert(update.buff_uuid, buff);
+        }
+        BuffChange::Removed => {
+            if let Some(buff) = stats.active_buffs.remove(&update.buff_uuid)
+                && let Some(uptime) = stats.buffs.get_mut(&buff.base_id)
+            {
+                uptime.stop(now);
+            }
+        }
+        BuffChange::Refreshed => {
+            if let Some(buff) = stats.active_buffs.get(&update.buff_uuid)
+                && let Some(uptime) = stats.buffs.get_mut(&buff.base_id)
+            {
+                uptime.refresh(now);
+            }
+        }
+        BuffChange::LayerAdded | BuffChange::LayerRemoved => {
+            if let Some(buff) = stats.active_buffs.get_mut(&update.buff_uuid) {
+                buff.layer = if matches!(update.change, BuffChange::LayerAdded) {
+                    buff.layer + 1
+                } else {
+                    (buff.layer - 1).max(1)
+                };
+                if let Some(uptime) = stats.buffs.get_mut(&buff.base_id) {
+                    uptime.set_layer(buff.layer, now);
+                }
+            }
+        }
+    }
+}
+
+/// End all running buffs, e.g. when the player leaves range and removals stop arriving
+pub fn end_active_buffs(stats: &mut PlayerStats) {
+    let now = Instant::now();
+    for (_, buff) in stats.active_buffs.drain() {
+        if let Some(uptime) = stats.buffs.get_mut(&buff.base_id) {
+            uptime.stop(now);
+        }
+    }
+}
+
+/// Fresh stats for the next encounter, keeping buffs still running on the player
+pub fn carry_active_buffs(stats: &PlayerStats) -> PlayerStats {
+    let now = Instant::now();
+    let mut next = PlayerStats::new(stats.uid);
+    next.name = stats.name.clone();
+    for (buff_uuid, buff) in &stats.active_buffs {
+        next.buffs.entry(buff.base_id).or_default().start(buff, now);
+        next.active_buffs.insert(*buff_uuid, buff.clone());
+    }
+    next
+}
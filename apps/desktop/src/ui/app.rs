@@ -6,12 +6,18 @@ use log::{info, warn};
 use crate::capture::packet;
 use crate::models::events;
 use crate::models::player::PlayerStats;
+use crate::protocol::constants::entity;
+use crate::stats::archive::{self, ArchivedEncounter};
 use crate::stats::{
-    process_damage_hit, process_damage_taken_hit, process_healing_hit, update_realtime_dps,
+    EncounterEndReason, EncounterEngine, MAX_ARCHIVED_ENCOUNTERS, end_active_buffs,
+    process_buff_update, process_damage_hit, process_damage_taken_hit, process_healing_hit,
+    process_healing_received, update_realtime_dps,
 };
 use crate::ui::components::title_bar;
 use crate::ui::constants::{app, colors, layout, radar, responsive, spacing, timing, window};
-use crate::ui::views::{combat_view, mob_view, radar_view, settings_view};
+use crate::ui::views::{
+    archive_view, combat_view, diagnostics_view, mob_view, radar_view, settings_view,
+};
 
 use crate::config::Settings;
 
@@ -20,12 +26,16 @@ pub enum ViewMode {
     Combat,
     Bosses,
     Settings,
+    Diagnostics,
+    Archive,
 }
 
 use crate::api::bptimer::BPTimerClient;
 use crate::api::pocketbase::PocketBaseClient;
+use crate::models::body_part::BodyPartTracker;
 use crate::models::mob::Mob;
 use crate::models::radar::RadarState;
+use crate::models::threat::ThreatState;
 use crate::utils::constants::is_tracked_mob;
 use std::sync::Arc;
 use tokio::sync::mpsc::{Receiver, channel};
@@ -42,6 +52,8 @@ pub struct DpsMeterApp {
     pub sort_column: Option<usize>,
     pub sort_descending: bool,
     pub view_mode: ViewMode,
+    pub combat_view_state: combat_view::CombatViewState,
+    pub archive_view_state: archive_view::ArchiveViewState,
     pub window_locked: bool,
 
     // Packet capture + player data
@@ -62,6 +74,18 @@ pub struct DpsMeterApp {
     // Radar State
     pub radar_state: RadarState,
 
+    // Local player's hate list
+    pub threat_state: ThreatState,
+
+    // Encounter segmentation and history of finished pulls
+    pub encounters: EncounterEngine,
+
+    // Encounters persisted across restarts (oldest first)
+    pub encounter_archive: Vec<ArchivedEncounter>,
+
+    // Breakable body parts of nearby bosses
+    pub body_part_tracker: BodyPartTracker,
+
     // Player info cache (name, class, ability score - persists across stats clearing)
     pub player_info_cache: crate::models::PlayerInfoCache,
 
@@ -147,6 +171,7 @@ impl DpsMeterApp {
     pub fn new(
         cc: &eframe::CreationContext<'_>,
         hotkey_manager: crate::hotkeys::HotkeyManager,
+        replay: Option<packet::ReplayOptions>,
     ) -> Self {
         let settings = Settings::load();
 
@@ -170,10 +195,16 @@ impl DpsMeterApp {
             }
         };
 
-        // Start packet capture
-        let packet_capture = packet::PacketCapture::start(settings.network_device_index);
-        if packet_capture.is_some() {
+        // Start packet capture (or replay a capture file)
+        let packet_capture = match &replay {
+            Some(options) => packet::PacketCapture::start_replay(options),
+            None => packet::PacketCapture::start(settings.network_device_index),
+        };
+        if let Some(capture) = &packet_capture {
             info!("Packet capture started successfully");
+            if settings.record_sessions && !capture.is_replay() {
+                capture.set_recording(true);
+            }
         } else {
             warn!("Failed to start packet capture - continuing without capture");
         }
@@ -209,6 +240,8 @@ impl DpsMeterApp {
             sort_column: settings.sort_column,
             sort_descending: settings.sort_descending,
             view_mode: ViewMode::Bosses,
+            combat_view_state: combat_view::CombatViewState::default(),
+            archive_view_state: archive_view::ArchiveViewState::default(),
             window_locked: false,
 
             packet_capture,
@@ -226,6 +259,11 @@ impl DpsMeterApp {
 
             radar_state: RadarState::new(),
 
+            threat_state: ThreatState::new(),
+            encounters: EncounterEngine::new(),
+            encounter_archive: archive::load_all(),
+            body_part_tracker: BodyPartTracker::new(),
+
             player_info_cache: crate::models::PlayerInfoCache::new(),
 
             class_icon_cache: crate::ui::components::class_icons::ClassIconCache::new(),
@@ -322,6 +360,11 @@ impl DpsMeterApp {
             return;
         }
 
+        // Never report HP from a replayed capture
+        if self.is_replay() {
+            return;
+        }
+
         let line = self.player_state.get_line_id();
         if line <= 0 {
             info!(
@@ -336,7 +379,7 @@ impl DpsMeterApp {
 
         let player_name = uid.and_then(|uid| self.player_info_cache.get_name(uid));
         let scene_ip = self.player_state.get_scene_ip();
-        if scene_ip.as_deref().map_or(true, |s| s.is_empty()) {
+        if scene_ip.as_deref().is_none_or(|s| s.is_empty()) {
             info!(
                 "[BPTimer] HP report skipped: mob_id={} line={} scene_ip is missing or empty",
                 mob_id, line
@@ -356,17 +399,59 @@ impl DpsMeterApp {
                 uid,
                 player_name,
                 scene_ip,
+                self.player_state.get_server_time_ms(),
             );
         }
     }
 
+    fn is_replay(&self) -> bool {
+        self.packet_capture
+            .as_ref()
+            .is_some_and(|capture| capture.is_replay())
+    }
+
     fn clear_combat_data(&mut self) {
+        self.finish_encounter(EncounterEndReason::Idle);
         self.player_stats.clear();
         self.dps_value = 0.0;
         self.total_damage = 0.0;
         self.max_dps = 0.0;
         self.dps_history = vec![0.0; app::DPS_HISTORY_SIZE];
         self.last_combat_event_time = None;
+        self.threat_state.clear();
+        self.body_part_tracker.clear();
+        self.player_state.cooldowns.reset_analysis();
+    }
+
+    /// Count a player hit towards the current encounter. The live meter keeps
+    /// accumulating across encounters until one of the clear mechanisms runs.
+    fn record_encounter_hit(&mut self) {
+        if self.encounters.record_hit(Instant::now()) {
+            self.player_state.cooldowns.reset_analysis();
+        }
+    }
+
+    /// Stats of a player in the active encounter
+    fn encounter_stats(&mut self, player_uid: i64) -> &mut PlayerStats {
+        let info_cache = &self.player_info_cache;
+        self.encounters
+            .player_stats_mut(player_uid, || info_cache.get_name_or_default(player_uid))
+    }
+
+    fn finish_encounter(&mut self, reason: EncounterEndReason) {
+        if let Some(encounter) = self.encounters.finish(reason, &self.player_state) {
+            info!("Encounter {} ended ({:?})", encounter.label(), reason);
+            let archived = ArchivedEncounter::from_encounter(encounter, &self.player_info_cache);
+            if let Err(e) = archive::append(&archived) {
+                warn!("Failed to archive encounter: {}", e);
+            }
+            if self.encounter_archive.len() >= MAX_ARCHIVED_ENCOUNTERS {
+                self.encounter_archive.remove(0);
+                let selected = &mut self.archive_view_state.selected;
+                *selected = selected.and_then(|index| index.checked_sub(1));
+            }
+            self.encounter_archive.push(archived);
+        }
     }
 
     fn ensure_player_stats_exists(&mut self, player_uid: i64) {
@@ -463,15 +548,15 @@ impl eframe::App for DpsMeterApp {
             self.mobs.clear();
 
             // Start new client if needed
-            if self.settings.show_mob_timers {
-                if let Some(region) = effective_region {
-                    let (pb_client, shutdown_tx, handle) =
-                        start_pocketbase_client(self.pb_sender.clone(), region);
-                    self.pb_client = Some(pb_client);
-                    self.pb_shutdown_tx = Some(shutdown_tx);
-                    self.pb_task_handle = Some(handle);
-                    info!("PocketBase client started with region");
-                }
+            if self.settings.show_mob_timers
+                && let Some(region) = effective_region
+            {
+                let (pb_client, shutdown_tx, handle) =
+                    start_pocketbase_client(self.pb_sender.clone(), region);
+                self.pb_client = Some(pb_client);
+                self.pb_shutdown_tx = Some(shutdown_tx);
+                self.pb_task_handle = Some(handle);
+                info!("PocketBase client started with region");
             }
             self.show_mob_timers_prev = self.settings.show_mob_timers;
             self.effective_region_prev = effective_region;
@@ -488,19 +573,40 @@ impl eframe::App for DpsMeterApp {
                     events::CombatEvent::Damage(hit) => {
                         if self.settings.show_combat_data {
                             self.last_combat_event_time = Some(Instant::now());
-                            self.ensure_player_stats_exists(hit.player_uid);
-                            let stats = self.player_stats.get_mut(&hit.player_uid).unwrap();
+                            self.record_encounter_hit();
+                            self.encounters.note_target(hit.target_base_id);
+                            self.encounters.record_damage(
+                                hit.player_uid,
+                                hit.damage,
+                                Instant::now(),
+                            );
+                            let boss_killed =
+                                hit.is_dead && hit.target_base_id.is_some_and(is_tracked_mob);
+                            let cutoff = self.settings.dps_calculation_cutoff_seconds;
+                            // Encounter totals are summed from its players
+                            let mut encounter_total = 0.0;
                             process_damage_hit(
-                                stats,
-                                &mut self.total_damage,
-                                hit,
-                                self.settings.dps_calculation_cutoff_seconds,
+                                self.encounter_stats(hit.player_uid),
+                                &mut encounter_total,
+                                hit.clone(),
+                                cutoff,
                             );
+                            self.ensure_player_stats_exists(hit.player_uid);
+                            let stats = self.player_stats.get_mut(&hit.player_uid).unwrap();
+                            process_damage_hit(stats, &mut self.total_damage, hit, cutoff);
+                            if boss_killed {
+                                self.finish_encounter(EncounterEndReason::BossDeath);
+                            }
                         }
                     }
                     events::CombatEvent::Healing(hit) => {
                         if self.settings.show_combat_data {
                             self.last_combat_event_time = Some(Instant::now());
+                            process_healing_received(self.encounter_stats(hit.target_uid), &hit);
+                            process_healing_hit(self.encounter_stats(hit.player_uid), hit.clone());
+                            self.ensure_player_stats_exists(hit.target_uid);
+                            let target = self.player_stats.get_mut(&hit.target_uid).unwrap();
+                            process_healing_received(target, &hit);
                             self.ensure_player_stats_exists(hit.player_uid);
                             let stats = self.player_stats.get_mut(&hit.player_uid).unwrap();
                             process_healing_hit(stats, hit);
@@ -509,17 +615,24 @@ impl eframe::App for DpsMeterApp {
                     events::CombatEvent::DamageTaken(hit) => {
                         if self.settings.show_combat_data {
                             self.last_combat_event_time = Some(Instant::now());
+                            self.encounters.touch(Instant::now());
+                            process_damage_taken_hit(
+                                self.encounter_stats(hit.player_uid),
+                                hit.clone(),
+                            );
                             self.ensure_player_stats_exists(hit.player_uid);
                             let stats = self.player_stats.get_mut(&hit.player_uid).unwrap();
                             process_damage_taken_hit(stats, hit);
                         }
                     }
                     events::CombatEvent::ServerChange(update) => {
+                        self.finish_encounter(EncounterEndReason::ServerChange);
                         if self.settings.clear_combat_data_on_server_change {
                             self.clear_combat_data();
                         }
                         self.radar_state.clear();
                         self.last_radar_update_time = None;
+                        self.player_state.cooldowns.clear();
                         self.current_server_endpoint = Some(update.server_endpoint);
                     }
                     events::CombatEvent::PlayerName(update) => {
@@ -529,6 +642,10 @@ impl eframe::App for DpsMeterApp {
                         if let Some(stats) = self.player_stats.get_mut(&update.player_uid) {
                             stats.name = update.name.clone();
                         }
+                        if let Some(stats) = self.encounters.get_player_stats_mut(update.player_uid)
+                        {
+                            stats.name = update.name.clone();
+                        }
                     }
                     events::CombatEvent::PlayerAccountInfo(update) => {
                         self.player_state
@@ -558,87 +675,159 @@ impl eframe::App for DpsMeterApp {
                             .set_ability_score(update.player_uid, update.ability_score);
                     }
                     events::CombatEvent::EntityPosition(update) => {
-                        if update.entity_type == crate::models::events::EntityType::Monster {
-                            if let Some(mob_base_id) = update.mob_base_id {
-                                if self.settings.show_radar || self.settings.bptimer_enabled {
-                                    self.radar_state.register_mob_uuid(update.uuid, mob_base_id);
-
-                                    if is_tracked_mob(mob_base_id) {
-                                        let is_hp_only_update = update.position.x
-                                            == f32::NEG_INFINITY
-                                            && update.position.y == f32::NEG_INFINITY
-                                            && update.position.z == f32::NEG_INFINITY;
-
-                                        if is_hp_only_update {
-                                            if self.radar_state.update_mob_hp(
-                                                mob_base_id,
-                                                update.current_hp,
-                                                update.max_hp,
-                                            ) {
-                                                self.last_radar_update_time = Some(Instant::now());
-                                            } else {
-                                                continue;
-                                            }
-                                        } else {
-                                            let mob_name = self
-                                                .mobs
-                                                .iter()
-                                                .find(|m| {
-                                                    m.id.parse::<u32>().unwrap_or(0) == mob_base_id
-                                                })
-                                                .map(|m| m.name.clone())
-                                                .unwrap_or_else(|| {
-                                                    crate::utils::constants::get_mob_name(
-                                                        mob_base_id,
-                                                    )
-                                                    .unwrap_or_else(|| {
-                                                        format!("Mob {}", mob_base_id)
-                                                    })
-                                                });
-
-                                            self.radar_state.update_mob_position(
-                                                mob_base_id,
-                                                mob_name,
-                                                update.position,
-                                                update.current_hp,
-                                                update.max_hp,
-                                            );
-                                            self.last_radar_update_time = Some(Instant::now());
-                                        }
+                        if update.entity_type == crate::models::events::EntityType::Monster
+                            && let Some(mob_base_id) = update.mob_base_id
+                            && (self.settings.show_radar || self.settings.bptimer_enabled)
+                        {
+                            self.radar_state.register_mob_uuid(update.uuid, mob_base_id);
+
+                            if is_tracked_mob(mob_base_id) {
+                                let is_hp_only_update = update.position.x == f32::NEG_INFINITY
+                                    && update.position.y == f32::NEG_INFINITY
+                                    && update.position.z == f32::NEG_INFINITY;
+
+                                if is_hp_only_update {
+                                    if self.radar_state.update_mob_hp(
+                                        mob_base_id,
+                                        update.current_hp,
+                                        update.max_hp,
+                                    ) {
+                                        self.last_radar_update_time = Some(Instant::now());
+                                    } else {
+                                        continue;
+                                    }
+                                } else {
+                                    let mob_name = self
+                                        .mobs
+                                        .iter()
+                                        .find(|m| m.id.parse::<u32>().unwrap_or(0) == mob_base_id)
+                                        .map(|m| m.name.clone())
+                                        .unwrap_or_else(|| {
+                                            crate::utils::constants::get_mob_name(mob_base_id)
+                                                .unwrap_or_else(|| format!("Mob {}", mob_base_id))
+                                        });
+
+                                    self.radar_state.update_mob_position(
+                                        mob_base_id,
+                                        mob_name,
+                                        update.position,
+                                        update.current_hp,
+                                        update.max_hp,
+                                    );
+                                    self.last_radar_update_time = Some(Instant::now());
+                                }
 
-                                        if let Some(mob) =
-                                            self.radar_state.tracked_mobs.get(&mob_base_id)
-                                        {
-                                            let hp_pct = mob.hp_percentage();
-                                            let position = mob.position;
-                                            let is_dead = match hp_pct {
-                                                Some(0) => true,
-                                                _ => mob.current_hp == Some(0),
-                                            };
-
-                                            if let Some(hp_pct) = hp_pct {
-                                                self.report_mob_hp(
-                                                    mob_base_id,
-                                                    hp_pct as f32,
-                                                    position,
-                                                );
-                                            }
-
-                                            if is_dead {
-                                                self.radar_state.remove_mob(mob_base_id);
-                                                continue;
-                                            }
-                                        }
+                                if let Some(mob) = self.radar_state.tracked_mobs.get(&mob_base_id) {
+                                    let hp_pct = mob.hp_percentage();
+                                    let position = mob.position;
+                                    let is_dead = match hp_pct {
+                                        Some(0) => true,
+                                        _ => mob.current_hp == Some(0),
+                                    };
+
+                                    if let Some(hp_pct) = hp_pct {
+                                        self.report_mob_hp(mob_base_id, hp_pct as f32, position);
+                                    }
+
+                                    if is_dead {
+                                        self.radar_state.remove_mob(mob_base_id);
+                                        continue;
                                     }
                                 }
                             }
                         }
                     }
+                    events::CombatEvent::Buff(update) => {
+                        if self.settings.show_combat_data {
+                            process_buff_update(
+                                self.encounter_stats(update.player_uid),
+                                update.clone(),
+                            );
+                            self.ensure_player_stats_exists(update.player_uid);
+                            let stats = self.player_stats.get_mut(&update.player_uid).unwrap();
+                            process_buff_update(stats, update);
+                        }
+                    }
+                    events::CombatEvent::HateList(update) => {
+                        if self.settings.show_combat_data {
+                            self.threat_state.update(update.entries);
+                        }
+                    }
+                    events::CombatEvent::BodyPartHit(hit) => {
+                        if self.settings.show_combat_data {
+                            self.body_part_tracker.record_hit(&hit);
+                        }
+                    }
+                    events::CombatEvent::BodyParts(update) => {
+                        if self.settings.show_combat_data {
+                            self.body_part_tracker.apply_update(&update);
+                        }
+                    }
+                    events::CombatEvent::SkillCooldowns(update) => {
+                        let now = Instant::now();
+                        let server_now_ms = self.player_state.get_server_time_ms();
+                        for cooldown in &update.cooldowns {
+                            self.player_state
+                                .cooldowns
+                                .apply(cooldown, now, server_now_ms);
+                        }
+                    }
+                    events::CombatEvent::EntityDisappear(update) => {
+                        self.threat_state.remove(update.uuid);
+                        self.body_part_tracker.remove(update.uuid);
+                        if entity::is_player(update.uuid) {
+                            let player_uid = entity::get_player_uid(update.uuid);
+                            if let Some(stats) = self.player_stats.get_mut(&player_uid) {
+                                end_active_buffs(stats);
+                            }
+                            if let Some(stats) = self.encounters.get_player_stats_mut(player_uid) {
+                                end_active_buffs(stats);
+                            }
+                            continue;
+                        }
+                        let Some((mob_base_id, mob)) = self
+                            .radar_state
+                            .remove_mob_uuid(update.uuid, update.mob_base_id)
+                        else {
+                            continue;
+                        };
+                        self.last_radar_update_time = Some(Instant::now());
+
+                        if update.reason.is_death() {
+                            let died_at = self
+                                .player_state
+                                .get_server_time_ms()
+                                .and_then(chrono::DateTime::from_timestamp_millis)
+                                .map(|t| t.format("%H:%M:%S UTC").to_string())
+                                .unwrap_or_else(|| "unknown server time".to_string());
+                            info!("[RADAR] {} ({}) died at {}", mob.name, mob_base_id, died_at);
+                            // Death may arrive before the final HP delta
+                            self.report_mob_hp(mob_base_id, 0.0, mob.position);
+                        } else {
+                            info!(
+                                "[RADAR] {} ({}) left range ({:?})",
+                                mob.name, mob_base_id, update.reason
+                            );
+                        }
+                    }
                     events::CombatEvent::LocalPlayerPosition(update) => {
                         if self.settings.show_radar || self.settings.bptimer_enabled {
                             self.radar_state.update_player_position(update.position);
                         }
                     }
+                    events::CombatEvent::ServerTime(update) => {
+                        let was_synced = self.player_state.server_clock.offset_ms().is_some();
+                        self.player_state.server_clock.add_sample(
+                            update.client_ms,
+                            update.server_ms,
+                            update.received_ms,
+                        );
+                        if !was_synced
+                            && let Some(offset) = self.player_state.server_clock.offset_ms()
+                        {
+                            info!("Server clock synced, local clock offset {} ms", offset);
+                        }
+                    }
                     events::CombatEvent::SceneIp(ip) => {
                         if ip.is_empty() {
                             if self.player_state.scene_ip.take().is_some() {
@@ -661,28 +850,44 @@ impl eframe::App for DpsMeterApp {
             );
         }
 
-        if let Some(idle_seconds) = self.settings.clear_combat_data_idle_seconds {
-            if let Some(last_event) = self.last_combat_event_time {
-                if last_event.elapsed().as_secs() >= idle_seconds {
-                    self.clear_combat_data();
-                    self.last_combat_event_time = None;
-                }
-            }
+        let in_combat = self.last_combat_event_time.is_some_and(|t| {
+            t.elapsed().as_secs_f32() < self.settings.dps_calculation_cutoff_seconds
+        });
+        self.player_state.cooldowns.tick(Instant::now(), in_combat);
+
+        // Hate lists are not cleared by the server once combat ends
+        if !in_combat && !self.threat_state.is_empty() {
+            self.threat_state.clear();
         }
 
-        if let Some(last_update) = self.last_radar_update_time {
-            if last_update.elapsed().as_secs() >= radar::IDLE_TIMEOUT_SECS {
-                self.radar_state.clear();
-                self.last_radar_update_time = None;
-            }
+        if self
+            .encounters
+            .is_idle(Instant::now(), self.settings.encounter_idle_seconds)
+        {
+            self.finish_encounter(EncounterEndReason::Idle);
         }
 
-        if let Some(timer) = self.settings_save_timer {
-            if timer.elapsed().as_millis() >= timing::SETTINGS_SAVE_DEBOUNCE_MS {
-                self.settings.save();
-                self.hotkey_manager.reload_from_settings(&self.settings);
-                self.settings_save_timer = None;
-            }
+        if let Some(idle_seconds) = self.settings.clear_combat_data_idle_seconds
+            && let Some(last_event) = self.last_combat_event_time
+            && last_event.elapsed().as_secs() >= idle_seconds
+        {
+            self.clear_combat_data();
+            self.last_combat_event_time = None;
+        }
+
+        if let Some(last_update) = self.last_radar_update_time
+            && last_update.elapsed().as_secs() >= radar::IDLE_TIMEOUT_SECS
+        {
+            self.radar_state.clear();
+            self.last_radar_update_time = None;
+        }
+
+        if let Some(timer) = self.settings_save_timer
+            && timer.elapsed().as_millis() >= timing::SETTINGS_SAVE_DEBOUNCE_MS
+        {
+            self.settings.save();
+            self.hotkey_manager.reload_from_settings(&self.settings);
+            self.settings_save_timer = None;
         }
 
         if self.update_check_requested {
@@ -732,51 +937,48 @@ impl eframe::App for DpsMeterApp {
                     None => true,
                 };
 
-                if should_handle {
-                    if let Some(action) = self.hotkey_manager.get_action(event.id) {
-                        match action {
-                            crate::hotkeys::HotkeyAction::ToggleClickThrough => {
-                                self.settings.click_through = !self.settings.click_through;
-                                self.settings.save();
-                                info!(
-                                    "Hotkey pressed: click-through toggled to {}",
-                                    self.settings.click_through
-                                );
-                            }
-                            crate::hotkeys::HotkeyAction::SwitchToMobView => {
-                                if self.settings.show_radar || self.settings.show_mob_timers {
-                                    self.view_mode = ViewMode::Bosses;
-                                    info!("Hotkey pressed: switched to mob view");
-                                }
-                            }
-                            crate::hotkeys::HotkeyAction::SwitchToCombatView => {
-                                if self.settings.show_combat_data {
-                                    self.view_mode = ViewMode::Combat;
-                                    info!("Hotkey pressed: switched to combat view");
-                                }
-                            }
-                            crate::hotkeys::HotkeyAction::MinimizeWindow => {
-                                let is_minimized =
-                                    ctx.input(|i| i.viewport().minimized.unwrap_or(false));
-                                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(
-                                    !is_minimized,
-                                ));
-                                info!(
-                                    "Hotkey pressed: minimize window (toggled from {})",
-                                    is_minimized
-                                );
+                if should_handle && let Some(action) = self.hotkey_manager.get_action(event.id) {
+                    match action {
+                        crate::hotkeys::HotkeyAction::ToggleClickThrough => {
+                            self.settings.click_through = !self.settings.click_through;
+                            self.settings.save();
+                            info!(
+                                "Hotkey pressed: click-through toggled to {}",
+                                self.settings.click_through
+                            );
+                        }
+                        crate::hotkeys::HotkeyAction::SwitchToMobView => {
+                            if self.settings.show_radar || self.settings.show_mob_timers {
+                                self.view_mode = ViewMode::Bosses;
+                                info!("Hotkey pressed: switched to mob view");
                             }
-                            crate::hotkeys::HotkeyAction::ResetStats => {
-                                self.dps_value = 0.0;
-                                self.total_damage = 0.0;
-                                self.max_dps = 0.0;
-                                self.dps_history = vec![0.0; app::DPS_HISTORY_SIZE];
-                                self.player_stats.clear();
-                                info!("Hotkey pressed: reset stats");
+                        }
+                        crate::hotkeys::HotkeyAction::SwitchToCombatView => {
+                            if self.settings.show_combat_data {
+                                self.view_mode = ViewMode::Combat;
+                                info!("Hotkey pressed: switched to combat view");
                             }
                         }
-                        self.last_hotkey_press = Some(now);
+                        crate::hotkeys::HotkeyAction::MinimizeWindow => {
+                            let is_minimized =
+                                ctx.input(|i| i.viewport().minimized.unwrap_or(false));
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(!is_minimized));
+                            info!(
+                                "Hotkey pressed: minimize window (toggled from {})",
+                                is_minimized
+                            );
+                        }
+                        crate::hotkeys::HotkeyAction::ResetStats => {
+                            self.dps_value = 0.0;
+                            self.total_damage = 0.0;
+                            self.max_dps = 0.0;
+                            self.dps_history = vec![0.0; app::DPS_HISTORY_SIZE];
+                            self.player_stats.clear();
+                            self.encounters.abort();
+                            info!("Hotkey pressed: reset stats");
+                        }
                     }
+                    self.last_hotkey_press = Some(now);
                 }
             }
         }
@@ -873,10 +1075,14 @@ impl eframe::App for DpsMeterApp {
                 let mut content_ui = ui.new_child(egui::UiBuilder::new().max_rect(content_rect));
 
                 content_ui.vertical(|ui| {
-                    let mut combat_players = if self.view_mode == ViewMode::Combat {
-                        combat_view::collect_active_players(&self.player_stats)
-                    } else {
-                        Vec::new()
+                    let archived_encounter = self
+                        .combat_view_state
+                        .selected_encounter
+                        .and_then(|id| self.encounters.get(id));
+                    let mut combat_players = match archived_encounter {
+                        _ if self.view_mode != ViewMode::Combat => Vec::new(),
+                        Some(encounter) => combat_view::collect_active_players(&encounter.players),
+                        None => combat_view::collect_active_players(&self.player_stats),
                     };
 
                     let reserve_height =
@@ -895,8 +1101,8 @@ impl eframe::App for DpsMeterApp {
 
                     scroll_area.show(ui, |ui| match self.view_mode {
                         ViewMode::Combat => {
-                            if self.settings.show_combat_data {
-                                if combat_view::render_combat_view(
+                            if self.settings.show_combat_data
+                                && combat_view::render_combat_view(
                                     ui,
                                     &mut combat_players,
                                     &mut self.sort_column,
@@ -905,13 +1111,21 @@ impl eframe::App for DpsMeterApp {
                                     &self.player_info_cache,
                                     &self.class_icon_cache,
                                     &self.player_state,
+                                    &mut self.combat_view_state,
+                                    &self.threat_state,
+                                    &self.body_part_tracker,
+                                    &self.encounters,
                                 ) {
-                                    combat_footer_text = Some(combat_view::dps_window_text(
-                                        &self.player_stats,
-                                        &self.settings,
-                                    ));
+                                    combat_footer_text = Some(match archived_encounter {
+                                        Some(encounter) => {
+                                            combat_view::format_duration_hms(encounter.duration_secs)
+                                        }
+                                        None => combat_view::dps_window_text(
+                                            &self.player_stats,
+                                            &self.settings,
+                                        ),
+                                    });
                                 }
-                            }
                         }
                         ViewMode::Bosses => {
                             ui.vertical(|ui| {
@@ -966,6 +1180,22 @@ impl eframe::App for DpsMeterApp {
                                 &mut self.update_perform_requested,
                                 &mut self.hotkey_manager,
                                 &mut self.hotkey_recording_state,
+                                &mut self.view_mode,
+                            );
+                        }
+                        ViewMode::Archive => {
+                            archive_view::render_archive_view(
+                                ui,
+                                &self.encounter_archive,
+                                &mut self.archive_view_state,
+                                &mut self.view_mode,
+                            );
+                        }
+                        ViewMode::Diagnostics => {
+                            diagnostics_view::render_diagnostics_view(
+                                ui,
+                                self.packet_capture.as_ref(),
+                                &mut self.view_mode,
                             );
                         }
                     });
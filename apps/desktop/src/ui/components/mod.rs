@@ -1,4 +1,5 @@
 pub mod class_icons;
 pub mod dps_graph;
 pub mod player_table;
+pub mod table;
 pub mod title_bar;
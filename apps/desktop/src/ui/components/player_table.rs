@@ -1,11 +1,133 @@
 use crate::config::Settings;
-use crate::models::{PlayerInfoCache, PlayerStats};
+use crate::models::{DamageSource, FilteredDamage, PlayerInfoCache, PlayerStats, SkillStats};
+use crate::protocol::pb::EEntityType;
 use crate::ui::components::class_icons;
 use crate::ui::constants::{player_table, spacing};
 use crate::utils::{constants, format_compact};
 use egui::{Color32, Ui};
 use egui_extras::{Column, TableBuilder};
 use egui_material_icons;
+use std::collections::HashSet;
+
+/// Click in a player row
+enum RowAction {
+    ShowBreakdown,
+    ToggleSources,
+}
+
+/// Display name of a damage source sub-row
+fn source_label(source: &DamageSource) -> String {
+    if source.is_direct() {
+        return "Self".to_string();
+    }
+    if let Some(base_id) = source.base_id {
+        return constants::get_mob_name(base_id).unwrap_or_else(|| format!("Summon {}", base_id));
+    }
+    match EEntityType::try_from(source.entity_type) {
+        Ok(EEntityType::EntPet) => "Pet".to_string(),
+        Ok(EEntityType::EntBullet | EEntityType::EntClientBullet) => "Projectiles".to_string(),
+        Ok(EEntityType::EntZone | EEntityType::EntField | EEntityType::EntTrap) => {
+            "Fields".to_string()
+        }
+        Ok(EEntityType::EntMonster) => "Summons".to_string(),
+        _ => format!("Entity type {}", source.entity_type),
+    }
+}
+
+/// Sources of a player's damage, largest first; empty if all damage was direct
+fn sorted_sources(player: &PlayerStats) -> Vec<(&DamageSource, &SkillStats)> {
+    if player.damage_sources.keys().all(DamageSource::is_direct) {
+        return Vec::new();
+    }
+    let mut sources: Vec<(&DamageSource, &SkillStats)> = player.damage_sources.iter().collect();
+    sources.sort_by(|a, b| {
+        b.1.total
+            .partial_cmp(&a.1.total)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    sources
+}
+
+/// One table row: a player and, under a target filter, their damage to the
+/// matching targets in place of their overall damage
+pub struct PlayerRow<'a> {
+    pub stats: &'a PlayerStats,
+    pub filtered: Option<FilteredDamage>,
+}
+
+impl<'a> PlayerRow<'a> {
+    pub fn new(stats: &'a PlayerStats) -> Self {
+        Self {
+            stats,
+            filtered: None,
+        }
+    }
+
+    pub fn total_damage(&self) -> f32 {
+        match &self.filtered {
+            Some(damage) => damage.total_damage,
+            None => self.stats.total_damage,
+        }
+    }
+
+    fn total_dps(&self, cutoff_seconds: f32) -> f32 {
+        match &self.filtered {
+            Some(damage) => damage.get_total_dps(cutoff_seconds),
+            None => self.stats.get_total_dps(cutoff_seconds),
+        }
+    }
+
+    fn effective_damage(&self) -> f32 {
+        match &self.filtered {
+            Some(damage) => damage.effective_damage,
+            None => self.stats.effective_damage,
+        }
+    }
+
+    fn overkill_damage(&self) -> f32 {
+        match &self.filtered {
+            Some(damage) => damage.overkill_damage,
+            None => self.stats.overkill_damage,
+        }
+    }
+
+    fn max_single_hit(&self) -> f32 {
+        match &self.filtered {
+            Some(damage) => damage.max_single_hit,
+            None => self.stats.max_single_hit,
+        }
+    }
+
+    /// (critical, lucky, total) hit counts
+    fn hit_counts(&self) -> (u32, u32, u32) {
+        match &self.filtered {
+            Some(damage) => (damage.critical_hits, damage.lucky_hits, damage.total_hits),
+            None => (
+                self.stats.critical_hits,
+                self.stats.lucky_hits,
+                self.stats.total_hits,
+            ),
+        }
+    }
+
+    fn crit_rate(&self) -> f32 {
+        let (critical, _, total) = self.hit_counts();
+        hit_rate(critical, total)
+    }
+
+    fn lucky_rate(&self) -> f32 {
+        let (_, lucky, total) = self.hit_counts();
+        hit_rate(lucky, total)
+    }
+}
+
+fn hit_rate(count: u32, total: u32) -> f32 {
+    if total > 0 {
+        ((count as f32) / (total as f32)) * 100.0
+    } else {
+        0.0
+    }
+}
 
 #[derive(Debug, Clone)]
 struct ColumnDef {
@@ -33,156 +155,128 @@ impl ColumnDef {
 
     fn value_for_player<'a>(
         &self,
-        player: &'a PlayerStats,
+        row: &PlayerRow<'a>,
         party_total_damage: f32,
         settings: &Settings,
         info_cache: &'a PlayerInfoCache,
         icon_cache: &'a class_icons::ClassIconCache,
+        expanded: Option<bool>,
     ) -> ColumnValue<'a> {
+        let player = row.stats;
         match self.index {
             0 => ColumnValue::LiveDps(player),
-            1 => ColumnValue::Name(&player.name, player.uid, info_cache, icon_cache),
+            1 => ColumnValue::Name(&player.name, player.uid, info_cache, icon_cache, expanded),
             2 => {
                 let pct = if party_total_damage > 0.0 {
-                    (player.total_damage / party_total_damage) * 100.0
-                } else {
-                    0.0
-                };
-                ColumnValue::Percentage(pct)
-            }
-            3 => {
-                ColumnValue::Compact(player.get_total_dps(settings.dps_calculation_cutoff_seconds))
-            }
-            4 => ColumnValue::Compact(player.total_damage),
-            5 => ColumnValue::Compact(player.max_single_hit),
-            6 => {
-                let pct = if player.total_hits > 0 {
-                    ((player.critical_hits as f32) / (player.total_hits as f32)) * 100.0
-                } else {
-                    0.0
-                };
-                ColumnValue::Percentage(pct)
-            }
-            7 => {
-                let pct = if player.total_hits > 0 {
-                    ((player.lucky_hits as f32) / (player.total_hits as f32)) * 100.0
+                    (row.total_damage() / party_total_damage) * 100.0
                 } else {
                     0.0
                 };
                 ColumnValue::Percentage(pct)
             }
+            3 => ColumnValue::Compact(row.total_dps(settings.dps_calculation_cutoff_seconds)),
+            4 => ColumnValue::Compact(row.total_damage()),
+            5 => ColumnValue::Compact(row.max_single_hit()),
+            6 => ColumnValue::Percentage(row.crit_rate()),
+            7 => ColumnValue::Percentage(row.lucky_rate()),
             8 => ColumnValue::Compact(player.total_healing),
             9 => ColumnValue::Compact(player.total_damage_taken),
+            10 => ColumnValue::Compact(row.effective_damage()),
+            11 => ColumnValue::Compact(row.overkill_damage()),
+            12 => ColumnValue::Compact(player.total_damage_absorbed),
             _ => ColumnValue::Empty,
         }
     }
 
-    fn compare_players(
+    /// Cell of a summon/pet sub-row; DPS is the owner's DPS scaled by the source share
+    fn value_for_source<'a>(
         &self,
-        a: &PlayerStats,
-        b: &PlayerStats,
+        player: &PlayerStats,
+        source: &DamageSource,
+        stats: &SkillStats,
         party_total_damage: f32,
         settings: &Settings,
-    ) -> std::cmp::Ordering {
+    ) -> ColumnValue<'a> {
         match self.index {
-            0 => std::cmp::Ordering::Equal,
-            1 => a.name.cmp(&b.name),
-            2 => {
-                let a_pct = if party_total_damage > 0.0 {
-                    (a.total_damage / party_total_damage) * 100.0
-                } else {
-                    0.0
-                };
-                let b_pct = if party_total_damage > 0.0 {
-                    (b.total_damage / party_total_damage) * 100.0
-                } else {
-                    0.0
-                };
-                a_pct
-                    .partial_cmp(&b_pct)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
-            3 => a
-                .get_total_dps(settings.dps_calculation_cutoff_seconds)
-                .partial_cmp(&b.get_total_dps(settings.dps_calculation_cutoff_seconds))
-                .unwrap_or(std::cmp::Ordering::Equal),
-            4 => a
-                .total_damage
-                .partial_cmp(&b.total_damage)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            5 => a
-                .max_single_hit
-                .partial_cmp(&b.max_single_hit)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            6 => {
-                let a_crit = if a.total_hits > 0 {
-                    ((a.critical_hits as f32) / (a.total_hits as f32)) * 100.0
-                } else {
-                    0.0
-                };
-                let b_crit = if b.total_hits > 0 {
-                    ((b.critical_hits as f32) / (b.total_hits as f32)) * 100.0
-                } else {
-                    0.0
-                };
-                a_crit
-                    .partial_cmp(&b_crit)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
-            7 => {
-                let a_lucky = if a.total_hits > 0 {
-                    ((a.lucky_hits as f32) / (a.total_hits as f32)) * 100.0
-                } else {
-                    0.0
-                };
-                let b_lucky = if b.total_hits > 0 {
-                    ((b.lucky_hits as f32) / (b.total_hits as f32)) * 100.0
+            1 => ColumnValue::SourceName(source_label(source)),
+            2 => ColumnValue::Percentage(stats.share(party_total_damage)),
+            3 => {
+                let share = if player.total_damage > 0.0 {
+                    stats.total / player.total_damage
                 } else {
                     0.0
                 };
-                a_lucky
-                    .partial_cmp(&b_lucky)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+                ColumnValue::Compact(
+                    player.get_total_dps(settings.dps_calculation_cutoff_seconds) * share,
+                )
             }
-            8 => a
-                .total_healing
-                .partial_cmp(&b.total_healing)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            9 => a
-                .total_damage_taken
-                .partial_cmp(&b.total_damage_taken)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            _ => std::cmp::Ordering::Equal,
+            4 => ColumnValue::Compact(stats.total),
+            5 => ColumnValue::Compact(stats.max_hit),
+            6 => ColumnValue::Percentage(stats.crit_rate()),
+            7 => ColumnValue::Percentage(stats.lucky_rate()),
+            _ => ColumnValue::Empty,
         }
     }
+
+    fn compare_players(
+        &self,
+        a: &PlayerRow<'_>,
+        b: &PlayerRow<'_>,
+        settings: &Settings,
+    ) -> std::cmp::Ordering {
+        let cutoff = settings.dps_calculation_cutoff_seconds;
+        let (a_value, b_value) = match self.index {
+            1 => return a.stats.name.cmp(&b.stats.name),
+            // Share of the same party total orders like the damage itself
+            2 | 4 => (a.total_damage(), b.total_damage()),
+            3 => (a.total_dps(cutoff), b.total_dps(cutoff)),
+            5 => (a.max_single_hit(), b.max_single_hit()),
+            6 => (a.crit_rate(), b.crit_rate()),
+            7 => (a.lucky_rate(), b.lucky_rate()),
+            8 => (a.stats.total_healing, b.stats.total_healing),
+            9 => (a.stats.total_damage_taken, b.stats.total_damage_taken),
+            10 => (a.effective_damage(), b.effective_damage()),
+            11 => (a.overkill_damage(), b.overkill_damage()),
+            12 => (a.stats.total_damage_absorbed, b.stats.total_damage_absorbed),
+            _ => return std::cmp::Ordering::Equal,
+        };
+        a_value
+            .partial_cmp(&b_value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
 }
 
 enum ColumnValue<'a> {
     LiveDps(&'a PlayerStats),
+    /// Name, uid, caches and the sub-row toggle state (None if not expandable)
     Name(
         &'a str,
         i64,
         &'a PlayerInfoCache,
         &'a class_icons::ClassIconCache,
+        Option<bool>,
     ),
+    SourceName(String),
     Percentage(f32),
     Compact(f32),
     Empty,
 }
 
 impl<'a> ColumnValue<'a> {
+    /// Render the cell; returns what was clicked in it, if anything
     fn render(
         &self,
         ui: &mut Ui,
         text_color: Color32,
         local_player_uid: Option<i64>,
         settings: &Settings,
-    ) {
+    ) -> Option<RowAction> {
+        let mut action = None;
         match self {
             ColumnValue::LiveDps(player) => {
                 crate::ui::components::dps_graph::render_dps_graph(ui, player, text_color);
             }
-            ColumnValue::Name(name, uid, info_cache, icon_cache) => {
+            ColumnValue::Name(name, uid, info_cache, icon_cache, expanded) => {
                 let old_spacing = {
                     let spacing = ui.spacing_mut();
                     let old = spacing.item_spacing;
@@ -192,6 +286,20 @@ impl<'a> ColumnValue<'a> {
 
                 ui.horizontal(|ui| {
                     ui.set_min_height(player_table::ROW_HEIGHT);
+                    if let Some(expanded) = expanded {
+                        let icon = if *expanded {
+                            egui_material_icons::icons::ICON_EXPAND_MORE
+                        } else {
+                            egui_material_icons::icons::ICON_CHEVRON_RIGHT
+                        };
+                        let toggle = ui.add(
+                            egui::Label::new(egui::RichText::new(icon.codepoint).color(text_color))
+                                .sense(egui::Sense::click()),
+                        );
+                        if toggle.on_hover_text("Show summon and pet damage").clicked() {
+                            action = Some(RowAction::ToggleSources);
+                        }
+                    }
                     let metadata = info_cache.get(*uid);
                     let is_local = local_player_uid == Some(*uid);
                     class_icons::render_class_icon(
@@ -207,7 +315,13 @@ impl<'a> ColumnValue<'a> {
                         .filter(|_| settings.show_ability_score_in_name)
                         .map(|score| format!("{} ({})", name, score))
                         .unwrap_or_else(|| name.to_string());
-                    let label = ui.colored_label(text_color, display_name);
+                    let label = ui.add(
+                        egui::Label::new(egui::RichText::new(display_name).color(text_color))
+                            .sense(egui::Sense::click()),
+                    );
+                    if label.clicked() {
+                        action = Some(RowAction::ShowBreakdown);
+                    }
                     label.on_hover_ui(|ui| {
                         ui.vertical(|ui| {
                             ui.label(format!("UID: {}", uid));
@@ -220,12 +334,19 @@ impl<'a> ColumnValue<'a> {
                             if let Some(score) = metadata.ability_score {
                                 ui.label(format!("Ability Score: {}", score));
                             }
+                            ui.label(egui::RichText::new("Click for skill breakdown").weak());
                         });
                     });
                 });
 
                 ui.spacing_mut().item_spacing = old_spacing;
             }
+            ColumnValue::SourceName(label) => {
+                ui.horizontal(|ui| {
+                    ui.add_space(player_table::SUB_ROW_INDENT);
+                    ui.label(egui::RichText::new(label).color(text_color).weak());
+                });
+            }
             ColumnValue::Percentage(pct) => {
                 ui.label(format!("{:.1}%", pct));
             }
@@ -236,11 +357,15 @@ impl<'a> ColumnValue<'a> {
             }
             ColumnValue::Empty => {}
         }
+        action
     }
 }
 
+/// Sparkline column, hidden when rows carry no live DPS (target filter)
+const LIVE_DPS_COLUMN: &str = "Live DPS";
+
 const COLUMNS: &[ColumnDef] = &[
-    ColumnDef::new("Live DPS", 0, false),
+    ColumnDef::new(LIVE_DPS_COLUMN, 0, false),
     ColumnDef::new("Name", 1, true),
     ColumnDef::new("DMG%", 2, true),
     ColumnDef::new("DPS", 3, true),
@@ -250,11 +375,15 @@ const COLUMNS: &[ColumnDef] = &[
     ColumnDef::new("Lucky%", 7, true),
     ColumnDef::new("Heal", 8, true),
     ColumnDef::new("Taken", 9, true),
+    ColumnDef::new("Eff. DMG", 10, true),
+    ColumnDef::new("Overkill", 11, true),
+    ColumnDef::new("Absorbed (taken)", 12, true),
 ];
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_player_table(
     ui: &mut Ui,
-    players: &mut Vec<&PlayerStats>,
+    rows: &mut [PlayerRow<'_>],
     party_total_damage: f32,
     sort_column: &mut Option<usize>,
     sort_descending: &mut bool,
@@ -262,25 +391,29 @@ pub fn render_player_table(
     info_cache: &PlayerInfoCache,
     icon_cache: &class_icons::ClassIconCache,
     player_state: &crate::models::PlayerState,
+    selected_player: &mut Option<i64>,
+    expanded_players: &mut HashSet<i64>,
+    live_dps: bool,
 ) {
     let visible_columns: Vec<&ColumnDef> = COLUMNS
         .iter()
         .filter(|col| !settings.hidden_columns.contains(col.name))
+        .filter(|col| live_dps || col.name != LIVE_DPS_COLUMN)
         .collect();
 
-    if let Some(sort_idx) = *sort_column {
-        if !visible_columns.iter().any(|col| col.index == sort_idx) {
-            *sort_column = None;
-        }
+    if let Some(sort_idx) = *sort_column
+        && !visible_columns.iter().any(|col| col.index == sort_idx)
+    {
+        *sort_column = None;
     }
 
-    if let Some(sort_idx) = *sort_column {
-        if let Some(col_def) = COLUMNS.iter().find(|c| c.index == sort_idx && c.sortable) {
-            players.sort_by(|a, b| {
-                let cmp = col_def.compare_players(a, b, party_total_damage, settings);
-                if *sort_descending { cmp.reverse() } else { cmp }
-            });
-        }
+    if let Some(sort_idx) = *sort_column
+        && let Some(col_def) = COLUMNS.iter().find(|c| c.index == sort_idx && c.sortable)
+    {
+        rows.sort_by(|a, b| {
+            let cmp = col_def.compare_players(a, b, settings);
+            if *sort_descending { cmp.reverse() } else { cmp }
+        });
     }
 
     egui::ScrollArea::both()
@@ -348,7 +481,17 @@ pub fn render_player_table(
                     );
                     let local_player_uid = player_state.get_uid();
 
-                    for player in players.iter() {
+                    for player in rows.iter() {
+                        // The source split is not tracked per target
+                        let sources = if player.filtered.is_none() {
+                            sorted_sources(player.stats)
+                        } else {
+                            Vec::new()
+                        };
+                        let uid = player.stats.uid;
+                        let expanded = expanded_players.contains(&uid);
+                        let toggle_state = (!sources.is_empty()).then_some(expanded);
+
                         body.row(player_table::ROW_HEIGHT, |mut row| {
                             for col_def in &visible_columns {
                                 row.col(|ui| {
@@ -358,11 +501,44 @@ pub fn render_player_table(
                                         settings,
                                         info_cache,
                                         icon_cache,
+                                        toggle_state,
                                     );
-                                    value.render(ui, text_color, local_player_uid, settings);
+                                    match value.render(ui, text_color, local_player_uid, settings) {
+                                        Some(RowAction::ShowBreakdown) => {
+                                            *selected_player = Some(uid);
+                                        }
+                                        Some(RowAction::ToggleSources) => {
+                                            let was_expanded = expanded_players.remove(&uid);
+                                            if !was_expanded {
+                                                expanded_players.insert(uid);
+                                            }
+                                        }
+                                        None => {}
+                                    }
                                 });
                             }
                         });
+
+                        if !expanded {
+                            continue;
+                        }
+                        for (source, stats) in sources {
+                            body.row(player_table::ROW_HEIGHT, |mut row| {
+                                for col_def in &visible_columns {
+                                    row.col(|ui| {
+                                        col_def
+                                            .value_for_source(
+                                                player.stats,
+                                                source,
+                                                stats,
+                                                party_total_damage,
+                                                settings,
+                                            )
+                                            .render(ui, text_color, local_player_uid, settings);
+                                    });
+                                }
+                            });
+                        }
                     }
                 });
 
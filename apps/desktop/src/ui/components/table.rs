@@ -0,0 +1,63 @@
+use crate::utils::format_compact;
+use egui::Ui;
+use egui_extras::{Column, TableBody, TableBuilder};
+
+/// Compact number with the exact value on hover
+pub fn compact_cell(ui: &mut Ui, value: f32) {
+    let (compact, raw) = format_compact(value);
+    ui.label(compact).on_hover_text(raw);
+}
+
+/// Striped table with resizable auto-sized columns and a bold header row.
+/// The last column takes the remaining width.
+pub fn striped_table(
+    ui: &mut Ui,
+    id_salt: &str,
+    headers: &[&str],
+    body: impl FnOnce(TableBody<'_>),
+) {
+    ui.push_id(id_salt, |ui| {
+        build(TableBuilder::new(ui), headers, body);
+    });
+}
+
+/// `striped_table` that scrolls once it is taller than `max_height`
+pub fn scrolling_striped_table(
+    ui: &mut Ui,
+    id_salt: &str,
+    headers: &[&str],
+    max_height: f32,
+    body: impl FnOnce(TableBody<'_>),
+) {
+    ui.push_id(id_salt, |ui| {
+        build(
+            TableBuilder::new(ui).max_scroll_height(max_height),
+            headers,
+            body,
+        );
+    });
+}
+
+fn build(table: TableBuilder<'_>, headers: &[&str], body: impl FnOnce(TableBody<'_>)) {
+    let mut table = table
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
+    for idx in 0..headers.len() {
+        table = table.column(if idx == headers.len() - 1 {
+            Column::remainder().clip(true)
+        } else {
+            Column::auto().resizable(true)
+        });
+    }
+
+    table
+        .header(18.0, |mut header| {
+            for name in headers {
+                header.col(|ui| {
+                    ui.strong(*name);
+                });
+            }
+        })
+        .body(body);
+}
@@ -3,6 +3,7 @@ use egui::{Color32, Context, Rect, Ui, Vec2};
 use egui_material_icons;
 use log::warn;
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_title_bar(
     ui: &mut Ui,
     title_bar_rect: Rect,
@@ -353,10 +354,10 @@ pub fn render_title_bar(
         text_color,
     );
 
-    if bptimer_response.clicked() {
-        if let Err(e) = open::that(crate::utils::constants::BPTIMER_BASE_URL) {
-            warn!("Failed to open BPTimer website: {}", e);
-        }
+    if bptimer_response.clicked()
+        && let Err(e) = open::that(crate::utils::constants::BPTIMER_BASE_URL)
+    {
+        warn!("Failed to open BPTimer website: {}", e);
     }
 
     bptimer_response.on_hover_text("Open BPTimer");
@@ -106,6 +106,8 @@ pub mod player_table {
     pub const ROW_HEIGHT: f32 = 16.0;
     pub const ICON_SIZE: f32 = 16.0;
     pub const ICON_NAME_SPACING: f32 = 4.0;
+    /// Summon/pet sub-rows line up with the player name
+    pub const SUB_ROW_INDENT: f32 = ICON_SIZE + ICON_NAME_SPACING;
 }
 
 /// Radar view constants
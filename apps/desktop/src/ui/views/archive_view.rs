@@ -0,0 +1,410 @@
+use crate::stats::EncounterEndReason;
+use crate::stats::archive::ArchivedEncounter;
+use crate::ui::app::ViewMode;
+use crate::ui::components::table::{self, compact_cell};
+use crate::ui::constants::{player_table, spacing, style};
+use crate::utils::{constants, export};
+use chrono::{DateTime, Local, TimeDelta};
+use egui::Ui;
+use egui_plot::{Line, Plot, PlotPoints};
+use log::{info, warn};
+use std::collections::BTreeMap;
+
+/// Max height of the encounter list before it scrolls
+const LIST_MAX_HEIGHT: f32 = 200.0;
+
+/// Start date filter of the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateRange {
+    Today,
+    LastWeek,
+    LastMonth,
+    #[default]
+    All,
+}
+
+impl DateRange {
+    pub const ALL: [DateRange; 4] = [
+        DateRange::Today,
+        DateRange::LastWeek,
+        DateRange::LastMonth,
+        DateRange::All,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateRange::Today => "Today",
+            DateRange::LastWeek => "Last 7 days",
+            DateRange::LastMonth => "Last 30 days",
+            DateRange::All => "All time",
+        }
+    }
+
+    fn contains(self, started_at: DateTime<Local>, now: DateTime<Local>) -> bool {
+        match self {
+            DateRange::Today => started_at.date_naive() == now.date_naive(),
+            DateRange::LastWeek => now.signed_duration_since(started_at) <= TimeDelta::days(7),
+            DateRange::LastMonth => now.signed_duration_since(started_at) <= TimeDelta::days(30),
+            DateRange::All => true,
+        }
+    }
+}
+
+/// Archive browser UI state that is not persisted in settings
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveViewState {
+    pub date_range: DateRange,
+    /// Boss base id, None for any encounter
+    pub boss: Option<u32>,
+    pub map: Option<u32>,
+    pub line: Option<u32>,
+    /// Archive index of the encounter shown below the list
+    pub selected: Option<usize>,
+}
+
+impl ArchiveViewState {
+    fn matches(&self, encounter: &ArchivedEncounter, now: DateTime<Local>) -> bool {
+        let in_range = encounter
+            .started_at()
+            .is_some_and(|started_at| self.date_range.contains(started_at, now));
+        in_range
+            && self
+                .boss
+                .is_none_or(|boss| encounter.boss_base_id == Some(boss))
+            && self
+                .map
+                .is_none_or(|map| encounter.level_map_id == Some(map))
+            && self.line.is_none_or(|line| encounter.line_id == Some(line))
+    }
+}
+
+pub fn boss_label(encounter: &ArchivedEncounter) -> String {
+    encounter
+        .boss_name
+        .clone()
+        .unwrap_or_else(|| "Trash".to_string())
+}
+
+pub fn map_label(level_map_id: Option<u32>) -> String {
+    level_map_id
+        .map(|id| format!("Map {}", id))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_minutes(seconds: f32) -> String {
+    let secs = seconds.round().max(0.0) as i64;
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+fn format_started_at(encounter: &ArchivedEncounter) -> String {
+    encounter
+        .started_at()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// CSV and JSON export buttons; `encounter` is only built on click
+pub fn render_export_buttons(ui: &mut Ui, encounter: impl FnOnce() -> Option<ArchivedEncounter>) {
+    let csv = ui
+        .button("Export CSV")
+        .on_hover_text("One row per player, saved to Downloads")
+        .clicked();
+    let json = ui
+        .button("Export JSON")
+        .on_hover_text("Players, per-second timeline and skills, saved to Downloads")
+        .clicked();
+    if !csv && !json {
+        return;
+    }
+
+    let Some(encounter) = encounter() else {
+        warn!("No encounter to export");
+        return;
+    };
+    let result = if csv {
+        export::save_encounter_csv(&encounter)
+    } else {
+        export::save_encounter_json(&encounter)
+    };
+    match result {
+        Ok(path) => {
+            info!("Encounter exported to {}", path.display());
+            if let Some(parent) = path.parent() {
+                let _ = open::that(parent);
+            }
+        }
+        Err(e) => warn!("Failed to export encounter: {}", e),
+    }
+}
+
+/// "Any" plus every value seen in the archive
+fn render_filter_combo(
+    ui: &mut Ui,
+    id_salt: &str,
+    selected: &mut Option<u32>,
+    options: &BTreeMap<u32, String>,
+) {
+    let selected_text = selected
+        .and_then(|id| options.get(&id).cloned())
+        .unwrap_or_else(|| "Any".to_string());
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, "Any");
+            for (id, label) in options {
+                ui.selectable_value(selected, Some(*id), label);
+            }
+        });
+}
+
+fn render_filters(ui: &mut Ui, archive: &[ArchivedEncounter], state: &mut ArchiveViewState) {
+    let mut bosses = BTreeMap::new();
+    let mut maps = BTreeMap::new();
+    let mut lines = BTreeMap::new();
+    for encounter in archive {
+        if let Some(boss) = encounter.boss_base_id {
+            bosses.insert(boss, boss_label(encounter));
+        }
+        if let Some(map) = encounter.level_map_id {
+            maps.insert(map, map_label(Some(map)));
+        }
+        if let Some(line) = encounter.line_id {
+            lines.insert(line, format!("Line {}", line));
+        }
+    }
+
+    egui::Grid::new("archive_filters")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Date");
+            egui::ComboBox::from_id_salt("archive_date_filter")
+                .selected_text(state.date_range.label())
+                .show_ui(ui, |ui| {
+                    for range in DateRange::ALL {
+                        ui.selectable_value(&mut state.date_range, range, range.label());
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Boss");
+            render_filter_combo(ui, "archive_boss_filter", &mut state.boss, &bosses);
+            ui.end_row();
+
+            ui.label("Map");
+            render_filter_combo(ui, "archive_map_filter", &mut state.map, &maps);
+            ui.end_row();
+
+            ui.label("Line");
+            render_filter_combo(ui, "archive_line_filter", &mut state.line, &lines);
+            ui.end_row();
+        });
+}
+
+/// Matching encounters, newest first
+fn render_encounter_list(
+    ui: &mut Ui,
+    archive: &[ArchivedEncounter],
+    rows: &[usize],
+    selected: &mut Option<usize>,
+) {
+    let headers = [
+        "Date",
+        "Boss",
+        "Map",
+        "Line",
+        "Duration",
+        "Party DPS",
+        "Players",
+    ];
+
+    table::scrolling_striped_table(
+        ui,
+        "archive_encounters",
+        &headers,
+        LIST_MAX_HEIGHT,
+        |mut body| {
+            for &index in rows {
+                let encounter = &archive[index];
+                body.row(player_table::ROW_HEIGHT, |mut row| {
+                    row.col(|ui| {
+                        if ui
+                            .selectable_label(
+                                *selected == Some(index),
+                                format_started_at(encounter),
+                            )
+                            .clicked()
+                        {
+                            *selected = Some(index);
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(boss_label(encounter));
+                    });
+                    row.col(|ui| {
+                        ui.label(map_label(encounter.level_map_id));
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            encounter
+                                .line_id
+                                .map(|line| line.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.label(format_minutes(encounter.duration_secs));
+                    });
+                    row.col(|ui| compact_cell(ui, encounter.party_dps()));
+                    row.col(|ui| {
+                        ui.label(encounter.players.len().to_string());
+                    });
+                });
+            }
+        },
+    );
+}
+
+/// Party DPS timeline and per-player totals of one encounter
+fn render_encounter_detail(ui: &mut Ui, encounter: &ArchivedEncounter) {
+    let outcome = match encounter.end_reason {
+        EncounterEndReason::BossDeath => "kill",
+        EncounterEndReason::Idle => "ended idle",
+        EncounterEndReason::ServerChange => "left server",
+        EncounterEndReason::InProgress => "in progress",
+    };
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(format!(
+                "{} - {}, {} ({})",
+                boss_label(encounter),
+                format_started_at(encounter),
+                format_minutes(encounter.duration_secs),
+                outcome
+            ))
+            .strong(),
+        );
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            render_export_buttons(ui, || Some(encounter.clone()));
+        });
+    });
+    ui.add_space(spacing::SM);
+
+    let points: Vec<[f64; 2]> = encounter
+        .party_timeline()
+        .iter()
+        .enumerate()
+        .map(|(second, &damage)| [second as f64, damage as f64])
+        .collect();
+    if !points.is_empty() {
+        Plot::new("archive_party_dps")
+            .height(80.0)
+            .include_y(0.0)
+            .allow_zoom(false)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Party DPS", PlotPoints::new(points)).fill(0.0));
+            });
+        ui.add_space(spacing::SM);
+    }
+
+    let total_damage = encounter.total_damage();
+    let headers = [
+        "Name", "Class", "Score", "DPS", "DMG", "DMG%", "Heal", "Taken",
+    ];
+
+    table::striped_table(ui, "archive_players", &headers, |mut body| {
+        for player in &encounter.players {
+            body.row(player_table::ROW_HEIGHT, |mut row| {
+                row.col(|ui| {
+                    ui.label(&player.name);
+                });
+                row.col(|ui| {
+                    ui.label(
+                        player
+                            .class_id
+                            .and_then(constants::get_class_name)
+                            .unwrap_or("-"),
+                    );
+                });
+                row.col(|ui| {
+                    ui.label(
+                        player
+                            .ability_score
+                            .map(|score| score.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                    );
+                });
+                row.col(|ui| compact_cell(ui, player.dps));
+                row.col(|ui| compact_cell(ui, player.total_damage));
+                row.col(|ui| {
+                    let share = if total_damage > 0.0 {
+                        player.total_damage / total_damage * 100.0
+                    } else {
+                        0.0
+                    };
+                    ui.label(format!("{:.1}%", share));
+                });
+                row.col(|ui| compact_cell(ui, player.total_healing));
+                row.col(|ui| compact_cell(ui, player.total_damage_taken));
+            });
+        }
+    });
+}
+
+/// Browser for encounters persisted across restarts
+pub fn render_archive_view(
+    ui: &mut Ui,
+    archive: &[ArchivedEncounter],
+    state: &mut ArchiveViewState,
+    view_mode: &mut ViewMode,
+) {
+    ui.horizontal(|ui| {
+        ui.heading("Encounter Archive");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Back").clicked() {
+                *view_mode = ViewMode::Settings;
+            }
+        });
+    });
+    ui.add_space(spacing::SM);
+
+    if archive.is_empty() {
+        ui.label(egui::RichText::new("No encounters archived yet.").weak());
+        return;
+    }
+
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        render_filters(ui, archive, state);
+    });
+    ui.add_space(spacing::SM);
+
+    let now = Local::now();
+    let rows: Vec<usize> = (0..archive.len())
+        .rev()
+        .filter(|&index| state.matches(&archive[index], now))
+        .collect();
+    if state.selected.is_some_and(|index| !rows.contains(&index)) {
+        state.selected = None;
+    }
+
+    if rows.is_empty() {
+        ui.label(egui::RichText::new("No encounters match the filters.").weak());
+        return;
+    }
+    ui.label(
+        egui::RichText::new(format!("{} of {} encounters", rows.len(), archive.len()))
+            .small()
+            .weak(),
+    );
+    render_encounter_list(ui, archive, &rows, &mut state.selected);
+
+    if let Some(index) = state.selected {
+        ui.add_space(spacing::MD);
+        style::group_frame(ui).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            render_encounter_detail(ui, &archive[index]);
+        });
+    }
+}
@@ -0,0 +1,448 @@
+use crate::config::Settings;
+use crate::models::cooldown::CooldownTracker;
+use crate::models::{DeathRecap, PlayerInfoCache, PlayerStats, RecapEventKind, SkillStats};
+use crate::protocol::constants::entity;
+use crate::protocol::pb::{EDamageMode, EDamageProperty, EDamageSource};
+use crate::ui::components::table::{self, compact_cell};
+use crate::ui::constants::{player_table, spacing, theme};
+use crate::ui::views::combat_view::{self, BreakdownTab, CombatViewState};
+use crate::utils::format_compact;
+use egui::Ui;
+use instant::Instant;
+use std::collections::HashMap;
+
+fn skill_label(skill_id: i32) -> String {
+    format!("Skill {}", skill_id)
+}
+
+fn element_label(property: i32) -> String {
+    let label = match EDamageProperty::try_from(property) {
+        Ok(EDamageProperty::DamagePropertyGeneral) => "General",
+        Ok(EDamageProperty::DamagePropertyFire) => "Fire",
+        Ok(EDamageProperty::DamagePropertyWater) => "Water",
+        Ok(EDamageProperty::DamagePropertyElectricity) => "Electricity",
+        Ok(EDamageProperty::DamagePropertyWood) => "Wood",
+        Ok(EDamageProperty::DamagePropertyWind) => "Wind",
+        Ok(EDamageProperty::DamagePropertyRock) => "Rock",
+        Ok(EDamageProperty::DamagePropertyLight) => "Light",
+        Ok(EDamageProperty::DamagePropertyDark) => "Dark",
+        Err(_) => return format!("Element {}", property),
+    };
+    label.to_string()
+}
+
+fn mode_label(mode: i32) -> String {
+    let label = match EDamageMode::try_from(mode) {
+        Ok(EDamageMode::DamageModeNormal) => "Untyped",
+        Ok(EDamageMode::DamageModePhysical) => "Physical",
+        Ok(EDamageMode::DamageModeMagical) => "Magical",
+        Err(_) => return format!("Mode {}", mode),
+    };
+    label.to_string()
+}
+
+fn source_type_label(source: i32) -> String {
+    let label = match EDamageSource::try_from(source) {
+        Ok(EDamageSource::DamageSourceSkill) => "Skill",
+        Ok(EDamageSource::DamageSourceBullet) => "Bullet",
+        Ok(EDamageSource::DamageSourceBuff) => "Buff (DoT)",
+        Ok(EDamageSource::DamageSourceFall) => "Fall",
+        Ok(EDamageSource::DamageSourceFakeBullet) => "Fake Bullet",
+        Ok(EDamageSource::DamageSourceOther) => "Other",
+        Err(_) => return format!("Source {}", source),
+    };
+    label.to_string()
+}
+
+/// Row of a breakdown table: label plus aggregated hits
+struct BreakdownRow<'a> {
+    label: String,
+    stats: &'a SkillStats,
+}
+
+fn keyed_rows(
+    breakdown: &HashMap<i32, SkillStats>,
+    label: fn(i32) -> String,
+) -> Vec<BreakdownRow<'_>> {
+    breakdown
+        .iter()
+        .map(|(key, stats)| BreakdownRow {
+            label: label(*key),
+            stats,
+        })
+        .collect()
+}
+
+fn target_rows<'a>(player: &'a PlayerStats, info_cache: &PlayerInfoCache) -> Vec<BreakdownRow<'a>> {
+    player
+        .damage_targets
+        .iter()
+        .map(|(uuid, target)| BreakdownRow {
+            label: combat_view::target_label(*uuid, target.base_id, info_cache),
+            stats: &target.damage,
+        })
+        .collect()
+}
+
+fn render_breakdown_table(
+    ui: &mut Ui,
+    id_salt: &str,
+    mut rows: Vec<BreakdownRow<'_>>,
+    total: f32,
+    label_header: &'static str,
+    value_header: &'static str,
+) {
+    rows.sort_by(|a, b| {
+        b.stats
+            .total
+            .partial_cmp(&a.stats.total)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    if rows.is_empty() {
+        ui.label(egui::RichText::new("No hits recorded.").weak());
+        return;
+    }
+
+    let headers = [
+        label_header,
+        value_header,
+        "Share",
+        "Hits",
+        "Crit%",
+        "Lucky%",
+        "Max Hit",
+        "Avg",
+    ];
+
+    table::striped_table(ui, id_salt, &headers, |mut body| {
+        for BreakdownRow {
+            label,
+            stats: skill,
+        } in rows
+        {
+            body.row(player_table::ROW_HEIGHT, |mut row| {
+                row.col(|ui| {
+                    ui.label(label);
+                });
+                row.col(|ui| compact_cell(ui, skill.total));
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", skill.share(total)));
+                });
+                row.col(|ui| {
+                    ui.label(skill.hits.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", skill.crit_rate()));
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", skill.lucky_rate()));
+                });
+                row.col(|ui| compact_cell(ui, skill.max_hit));
+                row.col(|ui| compact_cell(ui, skill.average_hit()));
+            });
+        }
+    });
+}
+
+fn caster_label(caster_uuid: i64, info_cache: &PlayerInfoCache) -> String {
+    if caster_uuid == 0 {
+        "-".to_string()
+    } else if entity::is_player(caster_uuid) {
+        info_cache.get_name_or_default(entity::get_player_uid(caster_uuid))
+    } else {
+        format!("Entity {}", caster_uuid)
+    }
+}
+
+/// Buff uptime over the encounter window, most uptime first
+fn render_buff_table(
+    ui: &mut Ui,
+    player: &PlayerStats,
+    info_cache: &PlayerInfoCache,
+    encounter_secs: f32,
+) {
+    let now = Instant::now();
+    let mut rows: Vec<_> = player.buffs.iter().collect();
+    rows.sort_by(|a, b| {
+        b.1.active_secs(now)
+            .partial_cmp(&a.1.active_secs(now))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    if rows.is_empty() {
+        ui.label(egui::RichText::new("No buffs recorded.").weak());
+        return;
+    }
+
+    let headers = [
+        "Buff",
+        "Uptime",
+        "Active",
+        "Applied",
+        "Refreshed",
+        "Avg Stacks",
+        "Max Stacks",
+        "Avg Duration",
+        "Caster",
+    ];
+
+    table::striped_table(ui, "breakdown_buffs", &headers, |mut body| {
+        for (base_id, buff) in rows {
+            body.row(player_table::ROW_HEIGHT, |mut row| {
+                row.col(|ui| {
+                    ui.label(format!("Buff {}", base_id));
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}%", buff.uptime(now, encounter_secs)));
+                });
+                row.col(|ui| {
+                    ui.label(if buff.is_active() { "Yes" } else { "" });
+                });
+                row.col(|ui| {
+                    ui.label(buff.applications.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(buff.refreshes.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}", buff.average_layer(now)));
+                });
+                row.col(|ui| {
+                    ui.label(buff.max_layer.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}s", buff.average_duration_secs(now)));
+                });
+                row.col(|ui| {
+                    ui.label(caster_label(buff.last_caster_uuid, info_cache));
+                });
+            });
+        }
+    });
+}
+
+fn death_label(index: usize, recap: &DeathRecap, info_cache: &PlayerInfoCache) -> String {
+    let killer = recap
+        .killing_blow()
+        .map(|hit| combat_view::target_label(hit.source_uuid, hit.source_base_id, info_cache))
+        .unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "Death {} at {} ({})",
+        index + 1,
+        recap.wall_time.format("%H:%M:%S"),
+        killer
+    )
+}
+
+/// Incoming hits and heals before the selected death, newest first
+fn render_death_recap(
+    ui: &mut Ui,
+    player: &PlayerStats,
+    selected_death: &mut Option<usize>,
+    info_cache: &PlayerInfoCache,
+) {
+    if player.death_recaps.is_empty() {
+        ui.label(egui::RichText::new("No deaths recorded.").weak());
+        return;
+    }
+
+    let last = player.death_recaps.len() - 1;
+    let mut index = selected_death.unwrap_or(last).min(last);
+    egui::ComboBox::from_id_salt("death_recap_selector")
+        .selected_text(death_label(index, &player.death_recaps[index], info_cache))
+        .show_ui(ui, |ui| {
+            for (i, recap) in player.death_recaps.iter().enumerate().rev() {
+                ui.selectable_value(&mut index, i, death_label(i, recap, info_cache));
+            }
+        });
+    *selected_death = (index != last).then_some(index);
+
+    let recap = &player.death_recaps[index];
+    let (taken, _) = format_compact(recap.total(RecapEventKind::Hit) as f32);
+    let (healed, _) = format_compact(recap.total(RecapEventKind::Heal) as f32);
+    ui.label(
+        egui::RichText::new(format!(
+            "Damage taken {}, healing received {}",
+            taken, healed
+        ))
+        .small()
+        .weak(),
+    );
+    ui.add_space(spacing::SM);
+
+    let headers = ["Time", "Type", "Source", "Skill", "Amount", "Absorbed"];
+
+    table::striped_table(ui, "breakdown_deaths", &headers, |mut body| {
+        for event in recap.events.iter().rev() {
+            body.row(player_table::ROW_HEIGHT, |mut row| {
+                row.col(|ui| {
+                    ui.label(format!("-{:.1}s", recap.seconds_before_death(event)));
+                });
+                row.col(|ui| {
+                    let (text, color) = match event.kind {
+                        RecapEventKind::Hit => ("Hit", egui::Color32::from_rgb(230, 110, 110)),
+                        RecapEventKind::Heal => ("Heal", egui::Color32::from_rgb(110, 200, 120)),
+                    };
+                    ui.label(egui::RichText::new(text).color(color));
+                });
+                row.col(|ui| {
+                    ui.label(combat_view::target_label(
+                        event.source_uuid,
+                        event.source_base_id,
+                        info_cache,
+                    ));
+                });
+                row.col(|ui| {
+                    ui.label(skill_label(event.skill_id));
+                });
+                row.col(|ui| compact_cell(ui, event.amount as f32));
+                row.col(|ui| compact_cell(ui, event.absorbed as f32));
+            });
+        }
+    });
+}
+
+/// Time each skill spent off cooldown but unused while in combat, most idle first
+fn render_cooldown_table(ui: &mut Ui, cooldowns: Option<&CooldownTracker>, encounter_secs: f32) {
+    let Some(cooldowns) = cooldowns else {
+        ui.label(egui::RichText::new("Cooldowns are only synced for your own character.").weak());
+        return;
+    };
+    if cooldowns.is_empty() {
+        ui.label(egui::RichText::new("No cooldowns recorded.").weak());
+        return;
+    }
+
+    let mut rows = cooldowns.skills();
+    rows.sort_by(|a, b| {
+        b.idle_secs
+            .partial_cmp(&a.idle_secs)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let headers = ["Skill", "CD", "Casts", "Idle off-CD", "Idle %"];
+
+    table::striped_table(ui, "breakdown_cooldowns", &headers, |mut body| {
+        for skill in rows {
+            body.row(player_table::ROW_HEIGHT, |mut row| {
+                row.col(|ui| {
+                    ui.label(skill_label(skill.skill_level_id));
+                });
+                row.col(|ui| {
+                    let text = format!("{:.1}s", skill.duration_ms as f32 / 1000.0);
+                    if skill.is_charge {
+                        ui.label(text).on_hover_text("Charge recharge time");
+                    } else {
+                        ui.label(text);
+                    }
+                });
+                row.col(|ui| {
+                    ui.label(skill.casts.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.1}s", skill.idle_secs));
+                });
+                row.col(|ui| {
+                    let share = if encounter_secs > 0.0 {
+                        (skill.idle_secs / encounter_secs * 100.0).min(100.0)
+                    } else {
+                        0.0
+                    };
+                    ui.label(format!("{:.1}%", share));
+                });
+            });
+        }
+    });
+}
+
+/// Per-skill and per-target breakdown of one player's damage and healing,
+/// plus buff uptime and idle cooldowns over `encounter_secs`. `cooldowns` is
+/// only set for the local player.
+pub fn render_breakdown_view(
+    ui: &mut Ui,
+    player: &PlayerStats,
+    view_state: &mut CombatViewState,
+    info_cache: &PlayerInfoCache,
+    settings: &Settings,
+    encounter_secs: f32,
+    cooldowns: Option<&CooldownTracker>,
+) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(info_cache.get_name_or_default(player.uid))
+                .strong()
+                .color(theme::text_color(settings)),
+        );
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Back").clicked() {
+                view_state.selected_player = None;
+                view_state.selected_death = None;
+            }
+        });
+    });
+    ui.add_space(spacing::SM);
+
+    ui.horizontal(|ui| {
+        for tab in BreakdownTab::ALL {
+            ui.selectable_value(&mut view_state.breakdown_tab, tab, tab.label());
+        }
+    });
+    ui.add_space(spacing::SM);
+
+    match view_state.breakdown_tab {
+        BreakdownTab::Damage => render_breakdown_table(
+            ui,
+            "breakdown_damage",
+            keyed_rows(&player.damage_skills, skill_label),
+            player.total_damage,
+            "Skill",
+            "DMG",
+        ),
+        BreakdownTab::Healing => render_breakdown_table(
+            ui,
+            "breakdown_healing",
+            keyed_rows(&player.healing_skills, skill_label),
+            player.total_healing,
+            "Skill",
+            "Heal",
+        ),
+        BreakdownTab::Targets => render_breakdown_table(
+            ui,
+            "breakdown_targets",
+            target_rows(player, info_cache),
+            player.total_damage,
+            "Target",
+            "DMG",
+        ),
+        BreakdownTab::Elements => render_breakdown_table(
+            ui,
+            "breakdown_elements",
+            keyed_rows(&player.damage_elements, element_label),
+            player.total_damage,
+            "Element",
+            "DMG",
+        ),
+        BreakdownTab::Modes => render_breakdown_table(
+            ui,
+            "breakdown_modes",
+            keyed_rows(&player.damage_modes, mode_label),
+            player.total_damage,
+            "Type",
+            "DMG",
+        ),
+        BreakdownTab::Sources => render_breakdown_table(
+            ui,
+            "breakdown_sources",
+            keyed_rows(&player.damage_source_types, source_type_label),
+            player.total_damage,
+            "Source",
+            "DMG",
+        ),
+        BreakdownTab::Buffs => render_buff_table(ui, player, info_cache, encounter_secs),
+        BreakdownTab::Deaths => {
+            render_death_recap(ui, player, &mut view_state.selected_death, info_cache)
+        }
+        BreakdownTab::Cooldowns => render_cooldown_table(ui, cooldowns, encounter_secs),
+    }
+}
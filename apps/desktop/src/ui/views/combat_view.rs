@@ -1,19 +1,419 @@
 use crate::config::Settings;
-use crate::models::PlayerStats;
-use crate::ui::components::player_table;
-use crate::ui::constants::{spacing, theme};
+use crate::models::body_part::BodyPartTracker;
+use crate::models::cooldown::CooldownTracker;
+use crate::models::threat::ThreatState;
+use crate::models::{PlayerStats, TargetStats};
+use crate::protocol::constants::entity;
+use crate::stats::EncounterEngine;
+use crate::stats::archive::ArchivedEncounter;
+use crate::ui::components::player_table::{self, PlayerRow};
+use crate::ui::constants::{spacing, style, theme};
+use crate::ui::views::{archive_view, breakdown_view};
+use crate::utils::constants;
+use crate::utils::format_compact;
 use egui::{Align, Layout, TextStyle, Ui};
 use instant::Instant;
 
-pub fn collect_active_players<'a>(
-    player_stats: &'a std::collections::HashMap<i64, PlayerStats>,
-) -> Vec<&'a PlayerStats> {
+/// Tabs of the per-player breakdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BreakdownTab {
+    #[default]
+    Damage,
+    Healing,
+    Targets,
+    Elements,
+    Modes,
+    Sources,
+    Buffs,
+    Deaths,
+    Cooldowns,
+}
+
+impl BreakdownTab {
+    pub const ALL: [BreakdownTab; 9] = [
+        BreakdownTab::Damage,
+        BreakdownTab::Healing,
+        BreakdownTab::Targets,
+        BreakdownTab::Elements,
+        BreakdownTab::Modes,
+        BreakdownTab::Sources,
+        BreakdownTab::Buffs,
+        BreakdownTab::Deaths,
+        BreakdownTab::Cooldowns,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BreakdownTab::Damage => "Damage",
+            BreakdownTab::Healing => "Healing",
+            BreakdownTab::Targets => "Targets",
+            BreakdownTab::Elements => "Element",
+            BreakdownTab::Modes => "Phys/Mag",
+            BreakdownTab::Sources => "Source",
+            BreakdownTab::Buffs => "Buffs",
+            BreakdownTab::Deaths => "Deaths",
+            BreakdownTab::Cooldowns => "Cooldowns",
+        }
+    }
+}
+
+/// Which damage the combat table counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetFilter {
+    #[default]
+    All,
+    /// Damage to tracked bosses and magical creatures only
+    TrackedBosses,
+    /// Damage to one target (raw uuid)
+    Target(i64),
+}
+
+impl TargetFilter {
+    pub fn matches(self, uuid: i64, target: &TargetStats) -> bool {
+        match self {
+            TargetFilter::All => true,
+            TargetFilter::TrackedBosses => target.base_id.is_some_and(constants::is_tracked_mob),
+            TargetFilter::Target(selected) => uuid == selected,
+        }
+    }
+}
+
+/// Combat view UI state that is not persisted in settings
+#[derive(Debug, Clone, Default)]
+pub struct CombatViewState {
+    /// Player whose breakdown is shown instead of the table
+    pub selected_player: Option<i64>,
+    pub breakdown_tab: BreakdownTab,
+    /// Death recap shown in the Deaths tab, None for the most recent
+    pub selected_death: Option<usize>,
+    pub target_filter: TargetFilter,
+    /// Players whose summon/pet sub-rows are expanded
+    pub expanded_players: std::collections::HashSet<i64>,
+    /// Finished encounter under review, None for the live one
+    pub selected_encounter: Option<u32>,
+}
+
+/// Display name of a damage target
+pub fn target_label(
+    uuid: i64,
+    base_id: Option<u32>,
+    info_cache: &crate::models::PlayerInfoCache,
+) -> String {
+    if let Some(base_id) = base_id {
+        return constants::get_mob_name(base_id).unwrap_or_else(|| format!("Monster {}", base_id));
+    }
+    if entity::is_player(uuid) {
+        return info_cache.get_name_or_default(entity::get_player_uid(uuid));
+    }
+    format!("Entity {}", uuid)
+}
+
+/// Targets hit by any player, most damaged first
+fn collect_targets(players: &[&PlayerStats]) -> Vec<(i64, Option<u32>, f32)> {
+    let mut targets: std::collections::HashMap<i64, (Option<u32>, f32)> =
+        std::collections::HashMap::new();
+    for player in players {
+        for (uuid, target) in &player.damage_targets {
+            let entry = targets.entry(*uuid).or_insert((None, 0.0));
+            entry.0 = entry.0.or(target.base_id);
+            entry.1 += target.damage.total;
+        }
+    }
+
+    let mut targets: Vec<(i64, Option<u32>, f32)> = targets
+        .into_iter()
+        .map(|(uuid, (base_id, total))| (uuid, base_id, total))
+        .collect();
+    targets.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    targets
+}
+
+/// Picker between the live encounter and finished ones, newest first, plus
+/// export of the shown encounter
+fn render_encounter_history(
+    ui: &mut Ui,
+    encounters: &EncounterEngine,
+    view_state: &mut CombatViewState,
+    player_state: &crate::models::PlayerState,
+    info_cache: &crate::models::PlayerInfoCache,
+) {
+    if encounters.history().is_empty() {
+        view_state.selected_encounter = None;
+    }
+
+    let previous = view_state.selected_encounter;
+    let selected_text = previous
+        .and_then(|id| encounters.get(id))
+        .map(|encounter| encounter.label())
+        .unwrap_or_else(|| "Live".to_string());
+
+    ui.horizontal(|ui| {
+        if !encounters.history().is_empty() {
+            ui.label("Encounter");
+            egui::ComboBox::from_id_salt("combat_encounter_history")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    let selected = &mut view_state.selected_encounter;
+                    ui.selectable_value(selected, None, "Live");
+                    ui.separator();
+                    for encounter in encounters.history().iter().rev() {
+                        ui.selectable_value(selected, Some(encounter.id), encounter.label());
+                    }
+                });
+        }
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            archive_view::render_export_buttons(ui, || {
+                // Live view exports the running encounter, or the last one
+                // once it has ended
+                let snapshot;
+                let encounter = match view_state.selected_encounter {
+                    Some(id) => encounters.get(id),
+                    None => {
+                        snapshot = encounters.snapshot(player_state);
+                        snapshot.as_ref().or_else(|| encounters.history().back())
+                    }
+                }?;
+                Some(ArchivedEncounter::from_encounter(encounter, info_cache))
+            });
+        });
+    });
+    if view_state.selected_encounter != previous {
+        view_state.selected_death = None;
+    }
+    ui.add_space(spacing::XS);
+}
+
+fn render_target_filter(
+    ui: &mut Ui,
+    players: &[&PlayerStats],
+    filter: &mut TargetFilter,
+    info_cache: &crate::models::PlayerInfoCache,
+) {
+    let targets = collect_targets(players);
+    if let TargetFilter::Target(uuid) = *filter
+        && !targets.iter().any(|(t, _, _)| *t == uuid)
+    {
+        *filter = TargetFilter::All;
+    }
+
+    let selected_text = match *filter {
+        TargetFilter::All => "All targets".to_string(),
+        TargetFilter::TrackedBosses => "Tracked bosses".to_string(),
+        TargetFilter::Target(uuid) => {
+            let base_id = targets
+                .iter()
+                .find(|(t, _, _)| *t == uuid)
+                .and_then(|(_, base_id, _)| *base_id);
+            target_label(uuid, base_id, info_cache)
+        }
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Damage to");
+        egui::ComboBox::from_id_salt("combat_target_filter")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(filter, TargetFilter::All, "All targets");
+                ui.selectable_value(filter, TargetFilter::TrackedBosses, "Tracked bosses");
+                if !targets.is_empty() {
+                    ui.separator();
+                }
+                for (uuid, base_id, _) in &targets {
+                    ui.selectable_value(
+                        filter,
+                        TargetFilter::Target(*uuid),
+                        target_label(*uuid, *base_id, info_cache),
+                    );
+                }
+            });
+    });
+    ui.add_space(spacing::XS);
+}
+
+/// Entities that have the local player on their hate list
+fn render_threat_panel(
+    ui: &mut Ui,
+    threat: &ThreatState,
+    info_cache: &crate::models::PlayerInfoCache,
+    settings: &Settings,
+) {
+    if threat.is_empty() {
+        return;
+    }
+
+    let warning_color = egui::Color32::from_rgb(230, 180, 80);
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal_wrapped(|ui| {
+            if threat.has_tracked_boss() {
+                ui.label(
+                    egui::RichText::new("Boss aggro")
+                        .strong()
+                        .color(warning_color),
+                );
+            } else {
+                ui.label(
+                    egui::RichText::new("Aggro")
+                        .strong()
+                        .color(theme::text_color(settings)),
+                );
+            }
+            for entry in threat.entries() {
+                let name = target_label(entry.uuid, entry.base_id, info_cache);
+                let is_boss = entry.base_id.is_some_and(constants::is_tracked_mob);
+                let text = egui::RichText::new(name);
+                ui.label(if is_boss {
+                    text.color(warning_color)
+                } else {
+                    text.color(theme::text_color(settings))
+                });
+            }
+        })
+        .response
+        .on_hover_text("Entities that currently have you on their hate list");
+    });
+    ui.add_space(spacing::XS);
+}
+
+fn part_damage_text(players: &[(i64, i64)], info_cache: &crate::models::PlayerInfoCache) -> String {
+    players
+        .iter()
+        .map(|(uid, damage)| {
+            let (compact, _) = format_compact(*damage as f32);
+            format!("{} {}", info_cache.get_name_or_default(*uid), compact)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Part HP bars and break times per boss, plus who dealt the most part damage
+fn render_body_part_panel(
+    ui: &mut Ui,
+    body_parts: &BodyPartTracker,
+    info_cache: &crate::models::PlayerInfoCache,
+    settings: &Settings,
+) {
+    if body_parts.is_empty() {
+        return;
+    }
+
+    let text_color = theme::text_color(settings);
+    let broken_color = egui::Color32::from_rgb(230, 110, 110);
+    let injured_color = egui::Color32::from_rgb(230, 170, 80);
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        for (uuid, entity) in body_parts.entities() {
+            ui.label(
+                egui::RichText::new(target_label(uuid, entity.base_id, info_cache))
+                    .strong()
+                    .color(text_color),
+            );
+            for (part_id, part) in &entity.parts {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("Part {}", part_id)).color(text_color));
+                    let (text, fill) = if part.is_broken() {
+                        let at = part
+                            .breaks
+                            .last()
+                            .map(|t| t.format("%H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        (format!("Broken {}", at), broken_color)
+                    } else if part.max_hp == 0 {
+                        ("HP unknown".to_string(), ui.visuals().selection.bg_fill)
+                    } else {
+                        let fill = if part.is_injured() {
+                            injured_color
+                        } else {
+                            ui.visuals().selection.bg_fill
+                        };
+                        (format!("{:.0}%", part.hp_fraction() * 100.0), fill)
+                    };
+                    let response = ui.add(
+                        egui::ProgressBar::new(part.hp_fraction())
+                            .text(text)
+                            .fill(fill),
+                    );
+                    let top = part.top_players(3);
+                    let mut hover = Vec::new();
+                    if !top.is_empty() {
+                        hover.push(format!(
+                            "Part damage: {}",
+                            part_damage_text(&top, info_cache)
+                        ));
+                    }
+                    if part.breaks.len() > 1 {
+                        let times: Vec<String> = part
+                            .breaks
+                            .iter()
+                            .map(|t| t.format("%H:%M:%S").to_string())
+                            .collect();
+                        hover.push(format!("Breaks: {}", times.join(", ")));
+                    }
+                    if !hover.is_empty() {
+                        response.on_hover_text(hover.join("\n"));
+                    }
+                });
+            }
+            let top = entity.top_players(3);
+            if !top.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Top part damage: {}",
+                        part_damage_text(&top, info_cache)
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
+            ui.add_space(spacing::XS);
+        }
+    });
+    ui.add_space(spacing::XS);
+}
+
+/// Remaining time of each local player skill, ready skills highlighted
+fn render_cooldown_strip(ui: &mut Ui, cooldowns: &CooldownTracker, settings: &Settings) {
+    if cooldowns.is_empty() {
+        return;
+    }
+
+    let now = Instant::now();
+    let ready_color = egui::Color32::from_rgb(110, 200, 120);
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal_wrapped(|ui| {
+            for skill in cooldowns.skills() {
+                let text = if skill.is_ready(now) {
+                    egui::RichText::new(format!("Skill {} ready", skill.skill_level_id))
+                        .color(ready_color)
+                } else {
+                    egui::RichText::new(format!(
+                        "Skill {} {:.1}s",
+                        skill.skill_level_id,
+                        skill.remaining_secs(now)
+                    ))
+                    .color(theme::text_color(settings))
+                };
+                ui.label(text.small());
+            }
+        });
+    });
+    ui.add_space(spacing::XS);
+    // Keep the countdown moving between packets
+    ui.ctx()
+        .request_repaint_after(std::time::Duration::from_millis(100));
+}
+
+pub fn collect_active_players(
+    player_stats: &std::collections::HashMap<i64, PlayerStats>,
+) -> Vec<&PlayerStats> {
     player_stats
         .values()
         .filter(|p| p.total_damage > 0.0 || p.total_healing > 0.0 || p.total_damage_taken > 0.0)
         .collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_combat_view(
     ui: &mut Ui,
     players: &mut Vec<&PlayerStats>,
@@ -23,7 +423,16 @@ pub fn render_combat_view(
     info_cache: &crate::models::PlayerInfoCache,
     icon_cache: &crate::ui::components::class_icons::ClassIconCache,
     player_state: &crate::models::PlayerState,
+    view_state: &mut CombatViewState,
+    threat: &ThreatState,
+    body_parts: &BodyPartTracker,
+    encounters: &EncounterEngine,
 ) -> bool {
+    render_encounter_history(ui, encounters, view_state, player_state, info_cache);
+    let archived = view_state
+        .selected_encounter
+        .and_then(|id| encounters.get(id));
+
     if players.is_empty() {
         ui.vertical_centered(|ui| {
             let available_height = ui.available_height();
@@ -41,11 +450,69 @@ pub fn render_combat_view(
         return false;
     }
 
-    let party_total_damage: f32 = players.iter().map(|p| p.total_damage).sum();
+    if let Some(uid) = view_state.selected_player {
+        match players.iter().find(|p| p.uid == uid) {
+            Some(player) => {
+                let cooldowns = match archived {
+                    Some(encounter) => {
+                        (encounter.local_uid == Some(uid)).then_some(&encounter.cooldowns)
+                    }
+                    None => {
+                        (player_state.get_uid() == Some(uid)).then_some(&player_state.cooldowns)
+                    }
+                };
+                let encounter_secs =
+                    calculate_dps_window_seconds(players.iter().copied(), settings).unwrap_or(0.0);
+                breakdown_view::render_breakdown_view(
+                    ui,
+                    player,
+                    view_state,
+                    info_cache,
+                    settings,
+                    encounter_secs,
+                    cooldowns,
+                );
+                return true;
+            }
+            None => view_state.selected_player = None,
+        }
+    }
+
+    // Live-only panels
+    if archived.is_none() {
+        if settings.show_threat_panel {
+            render_threat_panel(ui, threat, info_cache, settings);
+        }
+        if settings.show_body_parts {
+            render_body_part_panel(ui, body_parts, info_cache, settings);
+        }
+        if settings.show_cooldown_strip {
+            render_cooldown_strip(ui, &player_state.cooldowns, settings);
+        }
+    }
+    render_target_filter(ui, players, &mut view_state.target_filter, info_cache);
+
+    let filter = view_state.target_filter;
+    let mut rows: Vec<PlayerRow> = if filter == TargetFilter::All {
+        players.iter().map(|p| PlayerRow::new(p)).collect()
+    } else {
+        players
+            .iter()
+            .filter_map(|p| {
+                let damage = p.filtered_by_target(|uuid, target| filter.matches(uuid, target))?;
+                Some(PlayerRow {
+                    stats: p,
+                    filtered: Some(damage),
+                })
+            })
+            .collect()
+    };
+
+    let party_total_damage: f32 = rows.iter().map(PlayerRow::total_damage).sum();
 
     player_table::render_player_table(
         ui,
-        players,
+        &mut rows,
         party_total_damage,
         sort_column,
         sort_descending,
@@ -53,6 +520,10 @@ pub fn render_combat_view(
         info_cache,
         icon_cache,
         player_state,
+        &mut view_state.selected_player,
+        &mut view_state.expanded_players,
+        // The sparkline tracks damage to every target
+        filter == TargetFilter::All,
     );
 
     true
@@ -62,7 +533,7 @@ pub fn dps_window_text(
     player_stats: &std::collections::HashMap<i64, PlayerStats>,
     settings: &Settings,
 ) -> String {
-    calculate_dps_window_seconds(player_stats, settings)
+    calculate_dps_window_seconds(player_stats.values(), settings)
         .map(format_duration_hms)
         .unwrap_or_else(|| "--:--:--".to_string())
 }
@@ -89,14 +560,14 @@ pub fn footer_height(ui: &Ui) -> f32 {
     (spacing::XS * 2.0) + spacing + text_height
 }
 
-fn calculate_dps_window_seconds(
-    player_stats: &std::collections::HashMap<i64, PlayerStats>,
+fn calculate_dps_window_seconds<'a>(
+    player_stats: impl IntoIterator<Item = &'a PlayerStats>,
     settings: &Settings,
 ) -> Option<f32> {
     let mut earliest_start = None;
     let mut latest_end = None;
 
-    for stats in player_stats.values() {
+    for stats in player_stats {
         if let (Some(first), Some(last)) = (stats.first_damage_time, stats.last_damage_time) {
             earliest_start = Some(match earliest_start {
                 Some(current) => first.min(current),
@@ -125,7 +596,7 @@ fn calculate_dps_window_seconds(
     Some(duration.max(0.0))
 }
 
-fn format_duration_hms(seconds: f32) -> String {
+pub fn format_duration_hms(seconds: f32) -> String {
     let total_seconds = seconds.round().max(0.0) as i64;
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
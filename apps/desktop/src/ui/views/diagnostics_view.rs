@@ -0,0 +1,145 @@
+use crate::capture::diagnostics::DiagnosticsSnapshot;
+use crate::capture::packet;
+use crate::ui::app::ViewMode;
+use crate::ui::constants::{spacing, style};
+use egui::Ui;
+
+/// Integer with thousands separators (exact, unlike the f32-based formatters)
+fn format_count(n: u64) -> String {
+    let digits = n.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn counter_row(ui: &mut Ui, label: &str, value: u64) {
+    ui.label(label);
+    ui.label(format_count(value));
+    ui.end_row();
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KIB {
+        format!("{:.0} B", bytes)
+    } else if bytes < KIB * KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else if bytes < KIB * KIB * KIB {
+        format!("{:.1} MiB", bytes / (KIB * KIB))
+    } else {
+        format!("{:.2} GiB", bytes / (KIB * KIB * KIB))
+    }
+}
+
+pub fn render_diagnostics_view(
+    ui: &mut Ui,
+    packet_capture: Option<&packet::PacketCapture>,
+    view_mode: &mut ViewMode,
+) {
+    ui.horizontal(|ui| {
+        ui.heading("Capture Diagnostics");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Back").clicked() {
+                *view_mode = ViewMode::Settings;
+            }
+        });
+    });
+    ui.add_space(spacing::SM);
+
+    let Some(capture) = packet_capture else {
+        ui.label("Packet capture is not running.");
+        return;
+    };
+    let stats: DiagnosticsSnapshot = capture.diagnostics();
+
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.label(egui::RichText::new("Capture").strong());
+        ui.add_space(spacing::SM);
+
+        egui::Grid::new("diagnostics_capture")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                counter_row(ui, "Packets processed", stats.packets_processed);
+                if capture.is_replay() {
+                    ui.label("Packets received / dropped");
+                    ui.label(egui::RichText::new("n/a (replay)").weak());
+                    ui.end_row();
+                } else {
+                    counter_row(ui, "Packets received (pcap)", stats.pcap_received);
+                    counter_row(ui, "Packets dropped (pcap)", stats.pcap_dropped);
+                    counter_row(ui, "Dropped by interface", stats.pcap_if_dropped);
+                }
+            });
+
+        if stats.pcap_dropped > 0 || stats.pcap_if_dropped > 0 {
+            ui.add_space(spacing::SM);
+            ui.label(
+                egui::RichText::new(
+                    "Dropped packets mean the capture could not keep up; damage will be missing.",
+                )
+                .small()
+                .color(egui::Color32::from_rgb(230, 180, 80)),
+            );
+        }
+    });
+
+    ui.add_space(spacing::MD);
+
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.label(egui::RichText::new("TCP Reassembly").strong());
+        ui.add_space(spacing::SM);
+
+        egui::Grid::new("diagnostics_tcp")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Bytes reassembled");
+                ui.label(format_bytes(stats.bytes_reassembled));
+                ui.end_row();
+                counter_row(ui, "Frames extracted", stats.frames_extracted);
+                counter_row(ui, "Gap resyncs", stats.gap_resyncs);
+                ui.label("Bytes skipped");
+                ui.label(format_bytes(stats.bytes_skipped));
+                ui.end_row();
+            });
+    });
+
+    ui.add_space(spacing::MD);
+
+    style::group_frame(ui).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.label(egui::RichText::new("Parser").strong());
+        ui.add_space(spacing::SM);
+
+        egui::Grid::new("diagnostics_parser")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                counter_row(ui, "Zstd failures", stats.zstd_failures);
+                counter_row(ui, "Dirty data truncations", stats.dirty_truncated_objects);
+                for (message, count) in &stats.decode_failures {
+                    counter_row(ui, &format!("Decode failures: {}", message), *count);
+                }
+                for (method_id, count) in &stats.unknown_methods {
+                    counter_row(ui, &format!("Unknown method 0x{:08X}", method_id), *count);
+                }
+            });
+
+        if stats.decode_failures.is_empty() && stats.unknown_methods.is_empty() {
+            ui.label(
+                egui::RichText::new("No decode failures or unknown methods.")
+                    .small()
+                    .weak(),
+            );
+        }
+    });
+}
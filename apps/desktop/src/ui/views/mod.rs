@@ -1,4 +1,7 @@
+pub mod archive_view;
+pub mod breakdown_view;
 pub mod combat_view;
+pub mod diagnostics_view;
 pub mod mob_view;
 pub mod radar_view;
 pub mod settings_view;
@@ -1,6 +1,7 @@
 use crate::capture::packet;
 use crate::config::Settings;
 use crate::hotkeys::{HotkeyAction, HotkeyManager};
+use crate::ui::app::ViewMode;
 use crate::ui::constants::{responsive, spacing, style, theme};
 use egui::{Ui, Window};
 use global_hotkey::hotkey::{HotKey, Modifiers};
@@ -17,6 +18,7 @@ pub struct HotkeyRecordingState {
     was_recording: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_settings_view(
     ui: &mut Ui,
     settings: &mut Settings,
@@ -31,6 +33,7 @@ pub fn render_settings_view(
     update_perform_requested: &mut bool,
     hotkey_manager: &mut HotkeyManager,
     recording_state: &mut HotkeyRecordingState,
+    view_mode: &mut ViewMode,
 ) {
     // Handle hotkey recording
     let is_recording = recording_state.action.is_some();
@@ -393,6 +396,29 @@ pub fn render_settings_view(
 
             ui.add_space(spacing::MD);
 
+            ui.horizontal(|ui| {
+                ui.label("Encounter ends after idle:");
+                if ui
+                    .add(
+                        egui::Slider::new(&mut settings.encounter_idle_seconds, 5.0..=120.0)
+                            .text("sec")
+                            .step_by(1.0),
+                    )
+                    .changed()
+                {
+                    *settings_save_timer = Some(Instant::now());
+                }
+            });
+            ui.label(
+                egui::RichText::new(
+                    "A pull is archived to the encounter history after this many seconds without hits or when a tracked boss dies",
+                )
+                .small()
+                .weak(),
+            );
+
+            ui.add_space(spacing::MD);
+
             ui.horizontal(|ui| {
                 ui.label("Clear after idle (seconds):");
                 let mut idle_enabled = settings.clear_combat_data_idle_seconds.is_some();
@@ -440,6 +466,36 @@ pub fn render_settings_view(
                 *settings_save_timer = Some(Instant::now());
             }
 
+            if ui
+                .checkbox(
+                    &mut settings.show_threat_panel,
+                    "Show threat panel (who has you on their hate list)",
+                )
+                .changed()
+            {
+                *settings_save_timer = Some(Instant::now());
+            }
+
+            if ui
+                .checkbox(
+                    &mut settings.show_body_parts,
+                    "Show boss body parts (part HP, breaks, part damage)",
+                )
+                .changed()
+            {
+                *settings_save_timer = Some(Instant::now());
+            }
+
+            if ui
+                .checkbox(
+                    &mut settings.show_cooldown_strip,
+                    "Show skill cooldown strip",
+                )
+                .changed()
+            {
+                *settings_save_timer = Some(Instant::now());
+            }
+
             ui.add_space(spacing::SM);
 
             ui.label(
@@ -456,13 +512,20 @@ pub fn render_settings_view(
 
             let columns = [
                 "Live DPS", "Name", "DMG%", "DPS", "DMG", "Max Hit", "Crit%", "Lucky%", "Heal",
-                "Taken",
+                "Taken", "Eff. DMG", "Overkill", "Absorbed (taken)",
             ];
 
             for column_name in &columns {
                 let is_hidden = settings.hidden_columns.contains(*column_name);
                 let mut checked = !is_hidden;
-                if ui.checkbox(&mut checked, *column_name).changed() {
+                let mut checkbox = ui.checkbox(&mut checked, *column_name);
+                if *column_name == "Absorbed (taken)" {
+                    checkbox = checkbox.on_hover_text(
+                        "Damage the player's own shields absorbed. Hits do not name the \
+                         shield's caster, so supports are not credited for their shields.",
+                    );
+                }
+                if checkbox.changed() {
                     if checked {
                         settings.hidden_columns.remove(*column_name);
                     } else {
@@ -479,6 +542,19 @@ pub fn render_settings_view(
             ui.set_width(ui.available_width());
 
             ui.label("Network Device");
+            if let Some(path) = packet_capture.and_then(|c| c.replay_path()) {
+                ui.label(
+                    egui::RichText::new(format!("Replaying capture file: {}", path.display()))
+                        .small()
+                        .weak(),
+                );
+                ui.label(
+                    egui::RichText::new("HP reporting is disabled during replay.")
+                        .small()
+                        .weak(),
+                );
+                return;
+            }
             let mut device_changed = false;
             egui::ComboBox::from_id_salt("device_selector")
                 .selected_text(
@@ -745,6 +821,55 @@ pub fn render_settings_view(
                     .small()
                     .weak(),
             );
+
+            ui.add_space(spacing::SM);
+
+            let is_replay = packet_capture.is_some_and(|c| c.is_replay());
+            if ui
+                .add_enabled(
+                    !is_replay,
+                    egui::Checkbox::new(&mut settings.record_sessions, "Record Sessions"),
+                )
+                .changed()
+            {
+                if let Some(capture) = packet_capture {
+                    capture.set_recording(settings.record_sessions);
+                }
+                *settings_save_timer = Some(Instant::now());
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "Save reassembled game frames to {} for replay with --replay.",
+                    crate::capture::session::sessions_dir().display()
+                ))
+                .small()
+                .weak(),
+            );
+
+            ui.add_space(spacing::SM);
+
+            if ui.button("Encounter Archive").clicked() {
+                *view_mode = ViewMode::Archive;
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "Finished encounters, saved to {}.",
+                    crate::stats::archive::archive_path().display()
+                ))
+                .small()
+                .weak(),
+            );
+
+            ui.add_space(spacing::SM);
+
+            if ui.button("Capture Diagnostics").clicked() {
+                *view_mode = ViewMode::Diagnostics;
+            }
+            ui.label(
+                egui::RichText::new("Packet drops, reassembly and parser error counters.")
+                    .small()
+                    .weak(),
+            );
         });
     });
 
@@ -0,0 +1,194 @@
+use crate::stats::archive::ArchivedEncounter;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Same columns as the player table, minus the Live DPS sparkline
+const CSV_HEADERS: [&str; 12] = [
+    "Name",
+    "DMG%",
+    "DPS",
+    "DMG",
+    "Max Hit",
+    "Crit%",
+    "Lucky%",
+    "Heal",
+    "Taken",
+    "Eff. DMG",
+    "Overkill",
+    "Absorbed (taken)",
+];
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per player with raw numbers, ready to paste into a spreadsheet
+pub fn encounter_to_csv(encounter: &ArchivedEncounter) -> String {
+    let total_damage = encounter.total_damage();
+    let mut csv = CSV_HEADERS.join(",");
+    csv.push('\n');
+
+    for player in &encounter.players {
+        let share = if total_damage > 0.0 {
+            player.total_damage / total_damage * 100.0
+        } else {
+            0.0
+        };
+        let row = [
+            csv_field(&player.name),
+            format!("{:.1}", share),
+            format!("{:.0}", player.dps),
+            format!("{:.0}", player.total_damage),
+            format!("{:.0}", player.max_hit),
+            format!("{:.1}", player.crit_rate),
+            format!("{:.1}", player.lucky_rate),
+            format!("{:.0}", player.total_healing),
+            format!("{:.0}", player.total_damage_taken),
+            format!("{:.0}", player.effective_damage),
+            format!("{:.0}", player.overkill_damage),
+            format!("{:.0}", player.damage_absorbed),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Downloads path named after the encounter start time
+fn export_path(
+    encounter: &ArchivedEncounter,
+    extension: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = dirs::download_dir().ok_or("Could not find Downloads directory")?;
+    let stamp = encounter
+        .started_at()
+        .unwrap_or_else(chrono::Local::now)
+        .format("%Y%m%d-%H%M%S");
+    Ok(dir.join(format!("bptimer-encounter-{}.{}", stamp, extension)))
+}
+
+fn write_file(path: &PathBuf, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut f = fs::File::create(path)?;
+    f.write_all(content.as_bytes())?;
+    f.sync_all()?;
+    Ok(())
+}
+
+/// Save the per-player table of an encounter as CSV next to module exports
+pub fn save_encounter_csv(
+    encounter: &ArchivedEncounter,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = export_path(encounter, "csv")?;
+    write_file(&path, &encounter_to_csv(encounter))?;
+    Ok(path)
+}
+
+/// Save the full encounter (players, per-second timeline, skills) as JSON
+pub fn save_encounter_json(
+    encounter: &ArchivedEncounter,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = export_path(encounter, "json")?;
+    write_file(&path, &serde_json::to_string_pretty(encounter)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::EncounterEndReason;
+    use crate::stats::archive::ArchivedPlayer;
+
+    fn player(name: &str, total_damage: f32) -> ArchivedPlayer {
+        ArchivedPlayer {
+            uid: 1,
+            name: name.to_string(),
+            class_id: None,
+            ability_score: None,
+            dps: total_damage / 10.0,
+            total_damage,
+            max_hit: 0.0,
+            crit_rate: 0.0,
+            lucky_rate: 0.0,
+            total_healing: 0.0,
+            total_damage_taken: 0.0,
+            effective_damage: total_damage,
+            overkill_damage: 0.0,
+            damage_absorbed: 0.0,
+            dps_timeline: Vec::new(),
+            damage_skills: Vec::new(),
+            healing_skills: Vec::new(),
+        }
+    }
+
+    /// Split CSV into records of fields, honoring quoted fields
+    fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = csv.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                (true, '"') => quoted = false,
+                (true, c) => field.push(c),
+                (false, '"') => quoted = true,
+                (false, ',') => record.push(std::mem::take(&mut field)),
+                (false, '\n') => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                (false, c) => field.push(c),
+            }
+        }
+        records
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("Tester"), "Tester");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("cr\rlf"), "\"cr\rlf\"");
+    }
+
+    #[test]
+    fn csv_rows_match_header_columns() {
+        let encounter = ArchivedEncounter {
+            started_at_ms: 0,
+            duration_secs: 10.0,
+            boss_base_id: None,
+            boss_name: None,
+            end_reason: EncounterEndReason::Idle,
+            line_id: None,
+            level_map_id: None,
+            local_uid: None,
+            players: vec![
+                player("Plain", 300.0),
+                player("Comma, \"Quote\"\nNewline", 100.0),
+            ],
+        };
+
+        let records = parse_csv(&encounter_to_csv(&encounter));
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], CSV_HEADERS);
+        for record in &records {
+            assert_eq!(record.len(), CSV_HEADERS.len());
+        }
+        assert_eq!(records[2][0], "Comma, \"Quote\"\nNewline");
+        assert_eq!(records[1][1], "75.0");
+        assert_eq!(records[2][3], "100");
+    }
+}
@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod export;
 pub mod formatting;
 pub mod modules;
 
@@ -1,4 +1,4 @@
-use crate::protocol::pb::SyncContainerData;
+use crate::protocol::pb::{CharSerialize, SyncContainerData};
 use serde::{Deserialize, Serialize};
 
 /// Module data structure for encoding/decoding
@@ -48,11 +48,17 @@ pub fn is_valid_effect_id(effect_id: i32) -> bool {
 pub fn extract_modules(
     sync_data: &SyncContainerData,
 ) -> Result<Vec<Module>, Box<dyn std::error::Error>> {
-    let mut modules = Vec::new();
+    match &sync_data.v_data {
+        Some(v_data) => extract_modules_from_char(v_data),
+        None => Ok(Vec::new()),
+    }
+}
 
-    let Some(v_data) = &sync_data.v_data else {
-        return Ok(modules);
-    };
+/// Extract modules from a (possibly dirty-patched) CharSerialize
+pub fn extract_modules_from_char(
+    v_data: &CharSerialize,
+) -> Result<Vec<Module>, Box<dyn std::error::Error>> {
+    let mut modules = Vec::new();
 
     let Some(mod_data) = &v_data.r#mod else {
         return Ok(modules);
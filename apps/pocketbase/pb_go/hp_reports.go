@@ -270,6 +270,10 @@ func CreateHPReportHandler(app core.App) func(e *core.RequestEvent) error {
 		if data.PlayerName != "" {
 			hpReport.Set("player_name", data.PlayerName)
 		}
+		// A 0% report's server clock is when the mob died
+		if data.ServerTimeMs != 0 && data.HPPct == 0 {
+			hpReport.Set("died_at", time.UnixMilli(data.ServerTimeMs).UTC())
+		}
 
 		// Match the received position to the closest known location
 		if locationID, distSq := findClosestLocation(data.MonsterID, data.PosX, data.PosY, data.PosZ); locationID > 0 {
@@ -309,6 +313,9 @@ func CreateHPReportHandler(app core.App) func(e *core.RequestEvent) error {
 		if data.PlayerName != "" {
 			logArgs = append(logArgs, "player_name", data.PlayerName)
 		}
+		if data.ServerTimeMs != 0 {
+			logArgs = append(logArgs, "server_time", time.UnixMilli(data.ServerTimeMs).UTC().Format(time.RFC3339Nano))
+		}
 
 		app.Logger().Info("HP report saved", logArgs...)
 
@@ -5,16 +5,17 @@ import (
 )
 
 type CreateHPReportRequest struct {
-	MonsterID  int     `json:"monster_id" form:"monster_id"`             // Game monster ID (from meter)
-	HPPct      int     `json:"hp_pct" form:"hp_pct"`                     // HP percentage (0-100)
-	Channel    int     `json:"line" form:"line"`                         // Channel/Line number
-	PosX       float64 `json:"pos_x,omitempty" form:"pos_x"`             // X coordinate
-	PosY       float64 `json:"pos_y,omitempty" form:"pos_y"`             // Y coordinate
-	PosZ       float64 `json:"pos_z,omitempty" form:"pos_z"`             // Z coordinate
-	AccountID  string  `json:"account_id,omitempty" form:"account_id"`   // User account ID (pending implementation)
-	UID        int64   `json:"uid,omitempty" form:"uid"`                 // User Unique ID (pending implementation)
-	SceneIP    string  `json:"scene_ip,omitempty" form:"scene_ip"`       // Scene server IP for region identification
-	PlayerName string  `json:"player_name,omitempty" form:"player_name"` // Player name from game
+	MonsterID    int     `json:"monster_id" form:"monster_id"`                   // Game monster ID (from meter)
+	HPPct        int     `json:"hp_pct" form:"hp_pct"`                           // HP percentage (0-100)
+	Channel      int     `json:"line" form:"line"`                               // Channel/Line number
+	PosX         float64 `json:"pos_x,omitempty" form:"pos_x"`                   // X coordinate
+	PosY         float64 `json:"pos_y,omitempty" form:"pos_y"`                   // Y coordinate
+	PosZ         float64 `json:"pos_z,omitempty" form:"pos_z"`                   // Z coordinate
+	AccountID    string  `json:"account_id,omitempty" form:"account_id"`         // User account ID (pending implementation)
+	UID          int64   `json:"uid,omitempty" form:"uid"`                       // User Unique ID (pending implementation)
+	SceneIP      string  `json:"scene_ip,omitempty" form:"scene_ip"`             // Scene server IP for region identification
+	PlayerName   string  `json:"player_name,omitempty" form:"player_name"`       // Player name from game
+	ServerTimeMs int64   `json:"server_time_ms,omitempty" form:"server_time_ms"` // Game server clock when the HP was observed (ms since epoch)
 }
 
 type CachedMobData struct {
@@ -0,0 +1,22 @@
+/// <reference path="../pb_data/types.d.ts" />
+migrate(
+  (app) => {
+    const collection = app.findCollectionByNameOrId('hp_reports');
+    collection.fields.add(
+      new DateField({
+        hidden: false,
+        id: 'date_died_at',
+        name: 'died_at',
+        presentable: false,
+        required: false,
+        system: false
+      })
+    );
+    app.save(collection);
+  },
+  (app) => {
+    const collection = app.findCollectionByNameOrId('hp_reports');
+    collection.fields.removeById('date_died_at');
+    app.save(collection);
+  }
+);